@@ -0,0 +1,76 @@
+//! Compares the peak memory of rendering a large parse with
+//! `into_ascii_tree` and `write_ascii_tree_streaming`.
+//!
+//! ```text
+//! cargo run --release --example streaming_memory
+//! ```
+
+extern crate pest;
+#[macro_use]
+extern crate pest_derive;
+extern crate pest_ascii_tree;
+
+use pest::Parser;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Parser)]
+#[grammar = "expression.pest"]
+struct ExpressionParser;
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(current, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Throws the output away, like a terminal would.
+struct Sink;
+
+impl fmt::Write for Sink {
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// The peak of memory allocated while running `f`, beyond what was
+/// allocated before.
+fn peak_during<F: FnOnce()>(f: F) -> usize {
+    let before = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    f();
+    PEAK.load(Ordering::SeqCst) - before
+}
+
+fn main() {
+    let input = vec!["(a + (b - c))"; 20_000].join(" + ");
+    let pairs = ExpressionParser::parse(Rule::expr, &input).unwrap();
+
+    let materialized = peak_during(|| {
+        let output = pest_ascii_tree::into_ascii_tree(pairs.clone()).unwrap();
+        fmt::Write::write_str(&mut Sink, &output).unwrap();
+    });
+    let streamed = peak_during(|| {
+        pest_ascii_tree::write_ascii_tree_streaming(&mut Sink, pairs.clone()).unwrap();
+    });
+
+    println!("input:             {} bytes", input.len());
+    println!("into_ascii_tree:   {} bytes peak", materialized);
+    println!("streaming to sink: {} bytes peak", streamed);
+}
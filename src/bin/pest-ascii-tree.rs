@@ -0,0 +1,112 @@
+//! Prints the ascii tree of parsing the standard input with a grammar
+//! loaded at runtime.
+//!
+//! ```text
+//! pest-ascii-tree <grammar.pest> <rule> < input.txt
+//! ```
+
+extern crate pest_ascii_tree;
+extern crate pest_meta;
+extern crate pest_vm;
+
+use pest_meta::optimizer::OptimizedRule;
+use std::io::Read;
+use std::process;
+
+/// Why the tree couldn't be printed.
+#[derive(Debug, PartialEq, Eq)]
+enum CliError {
+    /// The arguments are invalid, exiting with code 2.
+    Usage(String),
+    /// Reading or parsing failed, exiting with code 1.
+    Failed(String),
+}
+
+/// Checks that the grammar defines the `rule`, listing the defined rules
+/// otherwise.
+fn check_rule(rule: &str, rules: &[OptimizedRule]) -> Result<(), CliError> {
+    if rules.iter().any(|defined| defined.name == rule) {
+        return Ok(());
+    }
+
+    let mut names: Vec<_> = rules.iter().map(|rule| rule.name.as_str()).collect();
+    names.sort_unstable();
+    Err(CliError::Usage(format!(
+        "unknown rule `{}`, the grammar defines: {}",
+        rule,
+        names.join(", ")
+    )))
+}
+
+fn run(grammar_path: &str, rule: &str) -> Result<(), CliError> {
+    let grammar = std::fs::read_to_string(grammar_path)
+        .map_err(|e| CliError::Failed(format!("{}: {}", grammar_path, e)))?;
+
+    let (_, rules) = pest_meta::parse_and_optimize(&grammar).map_err(|errors| {
+        CliError::Failed(
+            errors
+                .iter()
+                .map(|e| format!("{}: {}", grammar_path, e))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    })?;
+    check_rule(rule, &rules)?;
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| CliError::Failed(format!("<stdin>: {}", e)))?;
+
+    let vm = pest_vm::Vm::new(rules);
+    let pairs = vm
+        .parse(rule, &input)
+        .map_err(|e| CliError::Failed(e.to_string()))?;
+    let output =
+        pest_ascii_tree::into_ascii_tree(pairs).map_err(|e| CliError::Failed(e.to_string()))?;
+    print!("{}", output);
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <grammar.pest> <rule> < input", args[0]);
+        process::exit(2);
+    }
+
+    match run(&args[1], &args[2]) {
+        Ok(()) => {}
+        Err(CliError::Usage(e)) => {
+            eprintln!("{}", e);
+            eprintln!("usage: {} <grammar.pest> <rule> < input", args[0]);
+            process::exit(2);
+        }
+        Err(CliError::Failed(e)) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_rule() {
+        let (_, rules) = pest_meta::parse_and_optimize(
+            "expr = { val ~ (\"+\" ~ val)* }\nval = { ASCII_DIGIT+ }",
+        )
+        .unwrap();
+
+        assert_eq!(check_rule("expr", &rules), Ok(()));
+        assert_eq!(
+            check_rule("nosuch", &rules),
+            Err(CliError::Usage(
+                "unknown rule `nosuch`, the grammar defines: expr, val".to_string()
+            ))
+        );
+    }
+}
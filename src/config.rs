@@ -0,0 +1,464 @@
+use pest::{error::Error, iterators::Pairs};
+use std::collections::{HashMap, HashSet};
+
+/// How rule names are transformed for display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameCase {
+    /// The name as formatted by `Debug`, e.g. `func_def`.
+    Raw,
+    /// Underscores replaced by spaces and every word capitalized, e.g.
+    /// `Func Def`.
+    Title,
+    /// All letters uppercase, e.g. `FUNC_DEF`.
+    Upper,
+    /// All letters lowercase, e.g. `func_def`.
+    Lower,
+}
+
+impl NameCase {
+    pub(crate) fn apply(self, name: &str) -> String {
+        match self {
+            NameCase::Raw => name.to_string(),
+            NameCase::Title => {
+                let mut output = String::with_capacity(name.len());
+                let mut word_start = true;
+                for c in name.chars() {
+                    if c == '_' {
+                        output.push(' ');
+                        word_start = true;
+                    } else {
+                        if word_start {
+                            output.extend(c.to_uppercase());
+                        } else {
+                            output.push(c);
+                        }
+                        word_start = !c.is_alphanumeric();
+                    }
+                }
+                output
+            }
+            NameCase::Upper => name.to_uppercase(),
+            NameCase::Lower => name.to_lowercase(),
+        }
+    }
+}
+
+/// How the pairs are rendered if they contain more than one top-level
+/// tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MultiRootStyle {
+    /// The trees are rendered as siblings without a common root line.
+    Flatten,
+    /// Every tree gets an own parent node `[root N]`, counting from 0.
+    NumberedRoots,
+    /// The trees become the children of a root node with the given name.
+    NamedRoot(String),
+}
+
+/// Whether nodes with children also show their own matched text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeContentMode {
+    /// Only the rule name is shown, e.g. `expr`.
+    Hidden,
+    /// The length of the matched text in bytes, e.g. `expr (5 bytes)`.
+    Length,
+    /// The escaped text cut to the given number of columns, e.g.
+    /// `expr "a\ +…"`.
+    Summary(usize),
+    /// The escaped text like for leaves, e.g. `expr "a\ +\ b"`. It's
+    /// cut by [`TreeConfig::truncate_content`] as well.
+    ///
+    /// [`TreeConfig::truncate_content`]: struct.TreeConfig.html#structfield.truncate_content
+    Full,
+}
+
+/// Where [`TreeConfig::truncate_content`] cuts the content of leaves.
+///
+/// [`TreeConfig::truncate_content`]: struct.TreeConfig.html#structfield.truncate_content
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TruncateAt {
+    /// At the last character fitting, e.g. `"1,\ 22,\ 3…"`.
+    Hard,
+    /// After the last occurrence of the separator fitting, so tokens
+    /// aren't cut, e.g. `"1,\ 22,…"` for `','`. Content without the
+    /// separator before the limit is cut hard.
+    Separator(char),
+    /// Keeps the given number of columns of both ends, e.g. `"1,\ 2…33"`
+    /// for a head of 5 and a tail of 2, so closing delimiters stay
+    /// visible. Only applied to content wider than the limit.
+    HeadTail {
+        /// The columns kept of the start.
+        head: usize,
+        /// The columns kept of the end.
+        tail: usize,
+    },
+}
+
+/// The order of the top-level trees of the output, or of the sections
+/// rendered by [`render_forest`].
+///
+/// [`render_forest`]: fn.render_forest.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForestOrder {
+    /// The order of the input.
+    Input,
+    /// The largest tree first, by its number of nodes.
+    NodeCount,
+    /// Sorted by the name of the root rule.
+    RuleName,
+}
+
+/// Options controlling how the parse tree is turned into an ascii tree.
+///
+/// `TreeConfig::default()` produces the same output as
+/// [`into_ascii_tree`].
+///
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+#[derive(Clone, Debug)]
+pub struct TreeConfig {
+    /// Collapses chains of nodes having exactly one child into a single
+    /// line. The rule names of the chain are joined by `>`, so
+    /// `a > b > val "x"` is rendered as `a>b>val "x"`.
+    pub collapse_unary_chains: bool,
+
+    /// Cuts the escaped content of leaves wider than the given number of
+    /// columns, marking the cut with `…`.
+    ///
+    /// The width is measured per `char`, or by the display width of each
+    /// character with the `unicode-width` feature enabled, so full-width
+    /// characters are cut at the right visual width.
+    pub truncate_content: Option<usize>,
+
+    /// Where the content is cut by
+    /// [`truncate_content`](#structfield.truncate_content), at the last
+    /// character fitting by default.
+    pub truncate_at: TruncateAt,
+
+    /// Renders at most the given number of nodes in traversal order,
+    /// followed by a `… (truncated at N nodes)` line if nodes were left
+    /// out.
+    pub max_nodes: Option<usize>,
+
+    /// Names of rules left out of the tree together with all their inner
+    /// rules, in addition to the always skipped `EOI`.
+    pub skip_rules: Vec<String>,
+
+    /// Leaves out the last top-level tree regardless of its rule, for
+    /// grammars ending in a sentinel rule other than `EOI`. It's dropped
+    /// after the skipped rules, so `EOI` doesn't count as the last tree.
+    /// Nested nodes are never affected.
+    pub skip_last_top_level: bool,
+
+    /// Ignores ASCII case when comparing rule names against the names
+    /// given in the options, so `eoi` matches `EOI`.
+    ///
+    /// This only affects the comparisons, the displayed rule names stay
+    /// unchanged.
+    pub case_insensitive_rules: bool,
+
+    /// A line inserted between the top-level trees if the pairs contain
+    /// more than one of them, e.g. `Some("────".into())`. Only used with
+    /// [`MultiRootStyle::Flatten`] and [`MultiRootStyle::NumberedRoots`].
+    ///
+    /// [`MultiRootStyle::Flatten`]: enum.MultiRootStyle.html#variant.Flatten
+    /// [`MultiRootStyle::NumberedRoots`]: enum.MultiRootStyle.html#variant.NumberedRoots
+    pub root_separator: Option<String>,
+
+    /// Ends the output with a `\n`, which is the default. If disabled,
+    /// the last line of the tree isn't terminated.
+    pub trailing_newline: bool,
+
+    /// Transforms the displayed rule names, e.g. from `func_def` to
+    /// `Func Def`. Rule names given in the options are still compared
+    /// against the untransformed names.
+    pub name_transform: NameCase,
+
+    /// Leaves whose content is one of these literals, e.g. `,` or `;`,
+    /// are removed from their parent's children and summarized behind the
+    /// parent's name instead, like `list [, , ,]`.
+    pub fold_literal_leaves: HashSet<String>,
+
+    /// Leaves out leaves whose span is empty, like an optional trailing
+    /// rule which didn't match anything.
+    pub hide_empty_leaves: bool,
+
+    /// Prefixes the name of every leaf with the dotted names of its
+    /// ancestors, like `expr.expr.val "a"`. Internal nodes keep their
+    /// plain name.
+    pub leaf_path_prefix: bool,
+
+    /// Shows the depth of the node starting on each line in a left
+    /// gutter, like `1│ ├─ val "a"`. The gutter is as wide as the largest
+    /// depth.
+    pub show_depth_gutter: bool,
+
+    /// A line rendered instead of an empty tree, e.g. if all pairs were
+    /// skipped. Also used by [`into_ascii_tree_opt_with_config`] for
+    /// `None`, falling back to `(none)`.
+    ///
+    /// [`into_ascii_tree_opt_with_config`]: fn.into_ascii_tree_opt_with_config.html
+    pub empty_placeholder: Option<String>,
+
+    /// Renders nodes whose inner pairs were all filtered out, e.g. by
+    /// [`skip_rules`](#structfield.skip_rules), with an `(empty)` child
+    /// instead of as a leaf showing their content.
+    pub mark_emptied_nodes: bool,
+
+    /// The maximum number of columns of a line of the tree. Lines too
+    /// wide get abbreviated one by one: first the content of the leaf is
+    /// truncated, then the leading connector glyphs are elided to `…`
+    /// and finally the rest of the line is cut.
+    pub max_line_width: Option<usize>,
+
+    /// Shows the content of every leaf on its own line below the rule
+    /// name, indented like the children of the leaf would be.
+    pub content_on_new_line: bool,
+
+    /// Experimental: renders a subtree equal to an earlier one, with the
+    /// same rules and content, as a single line like
+    /// `expr ↑ same as line 2` instead of repeating it. This changes the
+    /// shape of the output, so it's disabled by default. Leaves are always
+    /// repeated and upside-down trees are never deduplicated.
+    pub dedup_subtrees: bool,
+
+    /// The number of columns every level is indented by, 3 by default,
+    /// like ` ├─`. With 2 the connectors are `├─` and with 1 just `├`.
+    pub indent_width: usize,
+
+    /// Replaces the content of leaves spanning multiple lines, like
+    /// comments or heredocs, with the number of lines, like
+    /// `block "<3 lines>"`.
+    pub multiline_summary: bool,
+
+    /// Removes the [`rule_prefix`](#structfield.rule_prefix) from the
+    /// start of the displayed rule names, for rule types whose `Debug`
+    /// output is qualified like `Rule::expr`.
+    pub strip_rule_prefix: bool,
+
+    /// The prefix removed by
+    /// [`strip_rule_prefix`](#structfield.strip_rule_prefix), `Rule::` by
+    /// default. Only a leading occurrence is removed.
+    pub rule_prefix: String,
+
+    /// Whether nodes with children also show their own matched text,
+    /// hidden by default.
+    pub node_content: NodeContentMode,
+
+    /// Wraps the escaped content of leaves wider than the given number of
+    /// columns onto further lines. They continue in the column the
+    /// content started in, with the connectors of the ancestors drawn in
+    /// front of them.
+    pub wrap_content: Option<usize>,
+
+    /// Shows the number of all nodes below every node with children,
+    /// like `expr (12)`. Leaves show no count.
+    pub show_descendant_count: bool,
+
+    /// Shows the number of direct children of every node with children,
+    /// after filtering, like `expr {3}`. Leaves show no count.
+    pub show_child_count: bool,
+
+    /// Folds the descendants of nodes at the given depth, the roots being
+    /// at depth 0, into the line of the node. A single path down to a
+    /// leaf is kept inline, like `expr [> expr > val "5"]`, while
+    /// branching descendants are elided to `expr […]`.
+    pub fold_beyond_depth: Option<usize>,
+
+    /// Prefixes every node with an id made of the indices of the node and
+    /// its ancestors among their siblings, like `[0.2.1] val "b"`. Unlike
+    /// traversal order, the id of a node doesn't change when other
+    /// branches change.
+    pub show_node_ids: bool,
+
+    /// Appends the bytes of the content of every leaf in hex, like
+    /// `val "ab" (0x61 0x62)`, to tell apart content the escaped form
+    /// leaves ambiguous.
+    pub show_hex: bool,
+
+    /// The order of the top-level trees and of the sections rendered by
+    /// [`render_forest`]. The nodes within a tree always keep their
+    /// order.
+    ///
+    /// [`render_forest`]: fn.render_forest.html
+    pub forest_order: ForestOrder,
+
+    /// Replaces every run of whitespace inside the content of leaves by a
+    /// single space, so snapshots don't change with the formatting of the
+    /// input. This alters the displayed content.
+    pub canonical: bool,
+
+    /// Merges adjacent sibling leaves of the same rule into a single line,
+    /// joining their contents by the
+    /// [`merge_separator`](#structfield.merge_separator), like
+    /// `digit "1, 2, 3"`.
+    pub merge_same_rule_leaves: bool,
+
+    /// The separator between the contents of leaves merged by
+    /// [`merge_same_rule_leaves`](#structfield.merge_same_rule_leaves),
+    /// `, ` by default.
+    pub merge_separator: String,
+
+    /// Glyphs put in front of the names of rules, like
+    /// `#️⃣ number "5"`, to tell kinds of nodes apart without colors. The
+    /// rules are looked up by name, after removing the
+    /// [`rule_prefix`](#structfield.rule_prefix) if it's stripped. Other
+    /// rules get no glyph.
+    pub rule_glyphs: HashMap<String, String>,
+
+    /// Renders leaves by their rule name only, like `val`, without their
+    /// content. The output then only depends on the shape of the tree,
+    /// so inputs of the same structure render the same.
+    pub hide_all_content: bool,
+
+    /// Renders the children of a node on a single line, like
+    /// `[val "a", op "+", val "b"]`, if there are at least two of them and
+    /// all are leaves. Nodes with any child having children of its own
+    /// are rendered as usual.
+    pub inline_leaf_siblings: bool,
+
+    /// Appends the value of leaves whose content is a number, like
+    /// `num "0xff" (255)` or `num "1.50" (1.5)`. Integers may be written
+    /// in decimal or with a `0x`, `0o` or `0b` prefix. Other content is
+    /// left untouched.
+    pub interpret_numbers: bool,
+
+    /// Pads the labels of leaves so their contents start in the same
+    /// column across the whole tree, like a table. This takes an extra
+    /// pass over all nodes measuring the labels before rendering. It has
+    /// no effect together with
+    /// [`content_on_new_line`](#structfield.content_on_new_line) or
+    /// [`hide_all_content`](#structfield.hide_all_content).
+    pub global_align: bool,
+
+    /// Mirrors the tree horizontally for right-to-left scripts: the lines
+    /// are aligned to the right, the connectors branch to the left like
+    /// `─┤` and the content of leaves comes before their rule name, like
+    /// `"a" val ─┤`.
+    pub rtl: bool,
+
+    /// Marks the leaf with the most characters of content by `» `, like
+    /// `» val "abc"`, to spot rules matching more than intended. Of
+    /// leaves equally long, the first in traversal order is marked.
+    pub highlight_longest_leaf: bool,
+
+    /// Shades the background of every line darker the deeper its node is,
+    /// using the grayscale of 256-color terminals. Beyond depth 7 the
+    /// shade stays the same. Nothing is shaded if the `NO_COLOR`
+    /// environment variable is set.
+    pub depth_gradient: bool,
+
+    /// Collapses every run of adjacent siblings of the same rule, whatever
+    /// their content and children, into a single line with the number of
+    /// nodes and their contents, like `digit ×4 ["1","2","3","4"]`. Runs
+    /// of top-level trees are rendered as usual.
+    pub collapse_same_rule_runs: bool,
+
+    /// Cuts rule names wider than the given number of columns, ending
+    /// them with `.`, like `func_de.` for `func_declaration` cut to 8
+    /// columns. If different rules of the tree end up with the same
+    /// abbreviation, a legend listing their full names follows the tree.
+    pub abbreviate_rules: Option<usize>,
+
+    /// The maximum number of columns of the content field of leaves,
+    /// including its quotes, regardless of the width of the rule name.
+    /// Wider content is cut with `…` inside the quotes, like `"123…"`.
+    /// Shorter fields followed by further information, like
+    /// [`show_hex`](#structfield.show_hex), are padded, so together with
+    /// [`global_align`](#structfield.global_align) the columns stay
+    /// uniform.
+    pub content_field_width: Option<usize>,
+
+    /// How more than one top-level tree is rendered. A single top-level
+    /// tree is always rendered as it is.
+    pub multi_root: MultiRootStyle,
+}
+
+impl Default for TreeConfig {
+    fn default() -> Self {
+        TreeConfig {
+            collapse_unary_chains: false,
+            truncate_content: None,
+            truncate_at: TruncateAt::Hard,
+            max_nodes: None,
+            skip_rules: Vec::new(),
+            skip_last_top_level: false,
+            case_insensitive_rules: false,
+            root_separator: None,
+            trailing_newline: true,
+            name_transform: NameCase::Raw,
+            fold_literal_leaves: HashSet::new(),
+            hide_empty_leaves: false,
+            leaf_path_prefix: false,
+            show_depth_gutter: false,
+            empty_placeholder: None,
+            mark_emptied_nodes: false,
+            max_line_width: None,
+            content_on_new_line: false,
+            dedup_subtrees: false,
+            indent_width: 3,
+            multiline_summary: false,
+            strip_rule_prefix: false,
+            rule_prefix: String::from("Rule::"),
+            node_content: NodeContentMode::Hidden,
+            wrap_content: None,
+            show_descendant_count: false,
+            show_child_count: false,
+            fold_beyond_depth: None,
+            show_node_ids: false,
+            show_hex: false,
+            forest_order: ForestOrder::Input,
+            canonical: false,
+            merge_same_rule_leaves: false,
+            merge_separator: String::from(", "),
+            rule_glyphs: HashMap::new(),
+            hide_all_content: false,
+            inline_leaf_siblings: false,
+            interpret_numbers: false,
+            global_align: false,
+            rtl: false,
+            highlight_longest_leaf: false,
+            depth_gradient: false,
+            collapse_same_rule_runs: false,
+            abbreviate_rules: None,
+            content_field_width: None,
+            multi_root: MultiRootStyle::Flatten,
+        }
+    }
+}
+
+impl TreeConfig {
+    /// Prints the ascii tree of the parsing result to stderr using these
+    /// options, or the error if parsing failed.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let config = pest_ascii_tree::TreeConfig {
+    ///     collapse_unary_chains: true,
+    ///     ..Default::default()
+    /// };
+    /// config.eprint(ExpressionParser::parse(Rule::expr, "((a))"));
+    /// ```
+    pub fn eprint<R>(&self, parsing_result: Result<Pairs<R>, Error<R>>)
+    where
+        R: pest::RuleType,
+    {
+        let _ = crate::print_ascii_tree_to(&mut std::io::stderr(), parsing_result, self);
+    }
+
+    /// Compares a rule name against a name given in the options.
+    pub(crate) fn rule_name_eq(&self, rule: &str, name: &str) -> bool {
+        if self.case_insensitive_rules {
+            rule.eq_ignore_ascii_case(name)
+        } else {
+            rule == name
+        }
+    }
+
+    pub(crate) fn is_skipped(&self, rule: &str) -> bool {
+        self.rule_name_eq(rule, "EOI")
+            || self
+                .skip_rules
+                .iter()
+                .any(|name| self.rule_name_eq(rule, name))
+    }
+}
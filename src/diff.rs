@@ -0,0 +1,329 @@
+use crate::config::TreeConfig;
+use crate::render;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// The operations turning the lines `old` into the lines `new`, based on
+/// their longest common subsequence. Within every run of changed lines,
+/// the removed ones come first.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+
+    let mut ops = Vec::with_capacity(prefix + old.len() + new.len() + suffix);
+    ops.resize(prefix, Op::Equal);
+    hirschberg(old, new, &mut ops);
+    ops.resize(ops.len() + suffix, Op::Equal);
+
+    for run in ops.split_mut(|op| *op == Op::Equal) {
+        run.sort_by_key(|op| *op == Op::Added);
+    }
+
+    ops
+}
+
+/// Appends the operations turning `old` into `new` to `ops`, splitting
+/// the problem in halves to only need linear space.
+fn hirschberg(old: &[&str], new: &[&str], ops: &mut Vec<Op>) {
+    match old {
+        [] => ops.resize(ops.len() + new.len(), Op::Added),
+        _ if new.is_empty() => ops.resize(ops.len() + old.len(), Op::Removed),
+        [line] => match new.iter().position(|other| other == line) {
+            Some(k) => {
+                ops.resize(ops.len() + k, Op::Added);
+                ops.push(Op::Equal);
+                ops.resize(ops.len() + new.len() - k - 1, Op::Added);
+            }
+            None => {
+                ops.push(Op::Removed);
+                ops.resize(ops.len() + new.len(), Op::Added);
+            }
+        },
+        _ => {
+            let mid = old.len() / 2;
+            let front = lcs_lengths(old[..mid].iter(), new.iter());
+            let back = lcs_lengths(old[mid..].iter().rev(), new.iter().rev());
+            let split = (0..=new.len())
+                .max_by_key(|&j| (front[j] + back[new.len() - j], std::cmp::Reverse(j)))
+                .unwrap_or_default();
+
+            hirschberg(&old[..mid], &new[..split], ops);
+            hirschberg(&old[mid..], &new[split..], ops);
+        }
+    }
+}
+
+/// The lengths of the longest common subsequences of all `old` lines and
+/// the first `j` lines of `new`, indexed by `j`.
+fn lcs_lengths<'a, 'l: 'a>(
+    old: impl Iterator<Item = &'a &'l str>,
+    new: impl Iterator<Item = &'a &'l str> + Clone,
+) -> Vec<usize> {
+    let mut row = vec![0; new.clone().count() + 1];
+    for line in old {
+        let mut diagonal = 0;
+        for (j, other) in new.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if line == other {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Renders the current tree, marking every line by how it differs from
+/// the `baseline` rendering.
+///
+/// Unchanged lines are prefixed by two spaces, lines replacing a line of
+/// the baseline by `~ ` and lines without counterpart in the baseline by
+/// `+ `. Lines only found in the baseline are not shown.
+///
+/// # Examples
+/// ```ignore
+/// let baseline = pest_ascii_tree::into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b")?)?;
+/// let output = pest_ascii_tree::highlight_changes(
+///                  &baseline, ExpressionParser::parse(Rule::expr, "a + c - d")?);
+/// assert_eq!(
+///     output,
+///     String::new()
+///         + "   expr\n"
+///         + "   ├─ val \"a\"\n"
+///         + "   ├─ op \"+\"\n"
+///         + "~  ├─ val \"c\"\n"
+///         + "+  ├─ op \"-\"\n"
+///         + "+  └─ val \"d\"\n"
+/// );
+/// ```
+pub fn highlight_changes<R>(baseline: &str, current_pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let current = match crate::into_ascii_tree(current_pairs) {
+        Ok(current) => current,
+        Err(e) => return format!("{}\n", e),
+    };
+
+    let old: Vec<&str> = baseline.lines().collect();
+    let new: Vec<&str> = current.lines().collect();
+
+    let mut output = String::with_capacity(current.len() + 2 * new.len());
+    let mut new_lines = new.iter();
+    let mut removed = 0;
+    for op in diff_lines(&old, &new) {
+        let marker = match op {
+            Op::Removed => {
+                removed += 1;
+                continue;
+            }
+            Op::Equal => {
+                removed = 0;
+                "  "
+            }
+            Op::Added if removed > 0 => {
+                removed -= 1;
+                "~ "
+            }
+            Op::Added => "+ ",
+        };
+        output.push_str(marker);
+        output.push_str(new_lines.next().unwrap());
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Puts the `marker` in front of the first line of the tree.
+fn mark(tree: ascii_tree::Tree, marker: &str) -> ascii_tree::Tree {
+    match tree {
+        ascii_tree::Tree::Node(title, children) => {
+            ascii_tree::Tree::Node(format!("{} {}", marker, title), children)
+        }
+        ascii_tree::Tree::Leaf(mut lines) => {
+            lines[0] = format!("{} {}", marker, lines[0]);
+            ascii_tree::Tree::Leaf(lines)
+        }
+    }
+}
+
+/// Merges the nodes at the same index of `old` and `new` into one list of
+/// trees.
+fn diff_siblings(
+    old: &[ParseTreeNode],
+    new: &[ParseTreeNode],
+    config: &TreeConfig,
+) -> Vec<ascii_tree::Tree> {
+    let mut trees = Vec::new();
+    for i in 0..old.len().max(new.len()) {
+        match (old.get(i), new.get(i)) {
+            (Some(old), Some(new)) if old.rule == new.rule && old.is_leaf() == new.is_leaf() => {
+                trees.push(diff_nodes(old, new, config));
+            }
+            (old, new) => {
+                if let Some(old) = old {
+                    trees.push(mark(render::into_ascii_tree_node(old, config), "-"));
+                }
+                if let Some(new) = new {
+                    trees.push(mark(render::into_ascii_tree_node(new, config), "+"));
+                }
+            }
+        }
+    }
+
+    trees
+}
+
+/// Merges two nodes of the same rule.
+fn diff_nodes(old: &ParseTreeNode, new: &ParseTreeNode, config: &TreeConfig) -> ascii_tree::Tree {
+    if new.is_leaf() {
+        if old.content() == new.content() {
+            return render::into_ascii_tree_node(new, config);
+        }
+        return ascii_tree::Tree::Leaf(vec![format!(
+            "{} \"{}\" → \"{}\"",
+            render::escape_rule_name(&new.rule),
+            render::default_escape(old.content()),
+            render::default_escape(new.content())
+        )]);
+    }
+
+    ascii_tree::Tree::Node(
+        render::escape_rule_name(&new.rule),
+        diff_siblings(&old.children, &new.children, config),
+    )
+}
+
+/// Renders the differences between two parse trees as a single tree.
+///
+/// The nodes are aligned by their position among their siblings. Leaves
+/// of the same rule with different content are shown inline, like
+/// `val "a" → "b"`. Nodes only found in the `old` tree are marked by `-`,
+/// nodes only found in the `new` one by `+`, like `+ op "-"`, with their
+/// whole subtree following them.
+///
+/// # Examples
+/// ```ignore
+/// let output = pest_ascii_tree::tree_diff(
+///                  ExpressionParser::parse(Rule::expr, "a + b")?,
+///                  ExpressionParser::parse(Rule::expr, "a + c - d")?);
+/// assert_eq!(
+///     output,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " ├─ val \"b\" → \"c\"\n"
+///         + " ├─ + op \"-\"\n"
+///         + " └─ + val \"d\"\n"
+/// );
+/// ```
+pub fn tree_diff<R>(old: Pairs<R>, new: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let trees = diff_siblings(
+        &tree::build(old, &config),
+        &tree::build(new, &config),
+        &config,
+    );
+
+    render::render_trees(&trees, &config).unwrap_or_else(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn lines() {
+        use Op::*;
+        assert_eq!(
+            diff_lines(&["a", "b", "c", "d", "e"], &["a", "x", "c", "y", "z", "e"]),
+            [Equal, Removed, Added, Equal, Removed, Added, Added, Equal]
+        );
+        assert_eq!(diff_lines(&["a"], &[]), [Removed]);
+        assert_eq!(diff_lines(&[], &["a"]), [Added]);
+    }
+
+    #[test]
+    fn highlight() {
+        let baseline =
+            crate::into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b").unwrap()).unwrap();
+
+        let output = highlight_changes(
+            &baseline,
+            ExpressionParser::parse(Rule::expr, "a + c - d").unwrap(),
+        );
+        assert_eq!(
+            output,
+            String::new()
+                + "   expr\n"
+                + "   ├─ val \"a\"\n"
+                + "   ├─ op \"+\"\n"
+                + "~  ├─ val \"c\"\n"
+                + "+  ├─ op \"-\"\n"
+                + "+  └─ val \"d\"\n"
+        );
+
+        let output = highlight_changes(
+            &baseline,
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+        );
+        assert!(output.lines().all(|line| line.starts_with("  ")));
+    }
+
+    #[test]
+    fn tree_diff_content() {
+        let output = tree_diff(
+            ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+            ExpressionParser::parse(Rule::expr, "a + (x - c)").unwrap(),
+        );
+        assert_eq!(
+            output,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr\n"
+                + "    ├─ val \"b\" → \"x\"\n"
+                + "    ├─ op \"-\"\n"
+                + "    └─ val \"c\"\n"
+        );
+
+        let output = tree_diff(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+        );
+        assert_eq!(
+            output,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ - expr\n"
+                + " │  └─ val \"b\"\n"
+                + " └─ + val \"b\"\n"
+        );
+    }
+}
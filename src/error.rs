@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// The error returned by the rendering functions of this crate.
+#[derive(Debug)]
+pub enum TreeError {
+    /// Writing the formatted tree failed.
+    Format(fmt::Error),
+    /// A rule not contained in the set of allowed rules was found.
+    UnexpectedRule {
+        /// The name of the rule.
+        rule: String,
+        /// The line the rule's span starts at, counting from 1.
+        line: usize,
+        /// The column the rule's span starts at, counting from 1.
+        col: usize,
+    },
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TreeError::Format(e) => write!(f, "failed to format the ascii tree: {}", e),
+            TreeError::UnexpectedRule { rule, line, col } => {
+                write!(f, "unexpected rule `{}` at {}:{}", rule, line, col)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl From<fmt::Error> for TreeError {
+    fn from(e: fmt::Error) -> Self {
+        TreeError::Format(e)
+    }
+}
@@ -0,0 +1,294 @@
+//! Checking the shape of a parse tree against an expectation, used by the
+//! [`assert_tree!`] macro.
+//!
+//! [`assert_tree!`]: macro.assert_tree.html
+
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+use std::fmt;
+
+/// The expected rule of a node and, optionally, of its children.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeExpectation {
+    rule: String,
+    children: Option<Vec<TreeExpectation>>,
+}
+
+impl TreeExpectation {
+    /// Expects a node of the rule, whatever its children are.
+    pub fn rule(rule: &str) -> Self {
+        TreeExpectation {
+            rule: rule.to_string(),
+            children: None,
+        }
+    }
+
+    /// Expects a node of the rule having exactly the given children.
+    pub fn node(rule: &str, children: Vec<TreeExpectation>) -> Self {
+        TreeExpectation {
+            rule: rule.to_string(),
+            children: Some(children),
+        }
+    }
+}
+
+fn check_nodes(
+    nodes: &[ParseTreeNode],
+    expected: &[TreeExpectation],
+    path: &str,
+) -> Result<(), String> {
+    if nodes.len() != expected.len() {
+        return Err(format!(
+            "expected {} nodes at {}, found {}",
+            expected.len(),
+            path,
+            nodes.len()
+        ));
+    }
+
+    for (i, (node, expected)) in nodes.iter().zip(expected).enumerate() {
+        let node_path = format!("{}[{}]", path, i);
+        if node.rule != expected.rule {
+            return Err(format!(
+                "expected rule `{}` at {}, found `{}`",
+                expected.rule, node_path, node.rule
+            ));
+        }
+        if let Some(children) = &expected.children {
+            check_nodes(
+                &node.children,
+                children,
+                &format!("{}/{}", node_path, node.rule),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the top-level nodes of the parsing result against `expected`.
+///
+/// On a mismatch, the error describes the first difference followed by
+/// the actual ascii tree.
+pub fn check_tree<R>(pairs: Pairs<R>, expected: &[TreeExpectation]) -> Result<(), String>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+
+    check_nodes(&nodes, expected, "root").map_err(|mismatch| {
+        let actual = crate::render::render(&nodes, &config).unwrap_or_else(|e| e.to_string());
+        format!("{}\nactual tree:\n{}", mismatch, actual)
+    })
+}
+
+/// The error returned by [`render_expecting`] if the shape of the tree
+/// differs from the expected one.
+///
+/// [`render_expecting`]: fn.render_expecting.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShapeMismatch {
+    /// The expected shape, without whitespace.
+    pub expected: String,
+    /// The actual shape, as returned by [`shape_signature`].
+    ///
+    /// [`shape_signature`]: fn.shape_signature.html
+    pub actual: String,
+    /// The actual ascii tree.
+    pub tree: String,
+}
+
+impl fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected shape `{}`, found `{}`\nactual tree:\n{}",
+            self.expected, self.actual, self.tree
+        )
+    }
+}
+
+impl std::error::Error for ShapeMismatch {}
+
+/// Formats the parsing result by pest into an ascii_tree, checking that
+/// its shape matches `expected_shape`.
+///
+/// The shape is written like by [`shape_signature`], e.g.
+/// `expr(val, op, expr(val))`, but may contain whitespace. On a match the
+/// ascii tree is returned, otherwise a [`ShapeMismatch`] holding the
+/// actual shape and tree, so a failing test shows the real tree.
+///
+/// # Examples
+/// ```ignore
+/// let tree = pest_ascii_tree::render_expecting(
+///                ExpressionParser::parse(Rule::expr, "a + (b)")?,
+///                "expr(val, op, expr(val))")?;
+/// assert!(tree.starts_with(" expr\n"));
+/// ```
+///
+/// [`shape_signature`]: fn.shape_signature.html
+/// [`ShapeMismatch`]: struct.ShapeMismatch.html
+pub fn render_expecting<R>(pairs: Pairs<R>, expected_shape: &str) -> Result<String, ShapeMismatch>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+    let tree = crate::render::render(&nodes, &config).unwrap_or_else(|e| e.to_string());
+
+    let expected: String = expected_shape
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let mut actual = String::new();
+    crate::export::write_shape(&mut actual, &nodes);
+
+    if actual == expected {
+        Ok(tree)
+    } else {
+        Err(ShapeMismatch {
+            expected,
+            actual,
+            tree,
+        })
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tree_expectations {
+    ([$($done:expr,)*]) => {
+        ::std::vec::Vec::<$crate::TreeExpectation>::from([$($done),*])
+    };
+    ([$($done:expr,)*] $rule:tt => [$($children:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::__tree_expectations!(
+            [$($done,)* $crate::TreeExpectation::node(
+                $rule,
+                $crate::__tree_expectations!([] $($children)*),
+            ),]
+            $($($rest)*)?
+        )
+    };
+    ([$($done:expr,)*] $rule:tt $(, $($rest:tt)*)?) => {
+        $crate::__tree_expectations!(
+            [$($done,)* $crate::TreeExpectation::rule($rule),]
+            $($($rest)*)?
+        )
+    };
+}
+
+/// Asserts that the rule names of the parsing result match the expected
+/// shape, panicking with the actual ascii tree otherwise.
+///
+/// The expectation lists the top-level rules. `"rule"` matches a node of
+/// the rule regardless of its children, `"rule" => [...]` additionally
+/// requires exactly the listed children, which can be nested further.
+///
+/// # Examples
+/// ```ignore
+/// pest_ascii_tree::assert_tree!(
+///     ExpressionParser::parse(Rule::expr, "a + (b)")?,
+///     "expr" => ["val", "op", "expr" => ["val"]]
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_tree {
+    ($pairs:expr, $($expected:tt)+) => {
+        if let Err(message) =
+            $crate::check_tree($pairs, &$crate::__tree_expectations!([] $($expected)+))
+        {
+            panic!("{}", message);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn assert_tree_macro() {
+        assert_tree!(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            "expr" => ["val", "op", "val"]
+        );
+        assert_tree!(
+            ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+            "expr" => ["val", "op", "expr" => ["val", "op", "val"]]
+        );
+        assert_tree!(
+            ExpressionParser::parse(Rule::expr_root, "a + (b)").unwrap(),
+            "val",
+            "op",
+            "expr"
+        );
+    }
+
+    #[test]
+    fn mismatch() {
+        let result = check_tree(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &__tree_expectations!([] "expr" => ["val", "op", "expr" => ["op"]]),
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            String::new()
+                + "expected rule `op` at root[0]/expr[2]/expr[0], found `val`\n"
+                + "actual tree:\n"
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr\n"
+                + "    └─ val \"b\"\n"
+        );
+
+        let result = check_tree(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &__tree_expectations!([] "expr" => ["val"]),
+        );
+        assert!(result
+            .unwrap_err()
+            .starts_with("expected 1 nodes at root[0]/expr, found 3\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "actual tree:")]
+    fn assert_tree_panics() {
+        assert_tree!(ExpressionParser::parse(Rule::val, "a").unwrap(), "op");
+    }
+
+    #[test]
+    fn render_expecting() {
+        let tree = super::render_expecting(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            "expr(val, op, expr(val))",
+        )
+        .unwrap();
+        assert_eq!(
+            tree,
+            crate::into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap())
+                .unwrap()
+        );
+
+        let mismatch = super::render_expecting(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            "expr(val, op, expr(val))",
+        )
+        .unwrap_err();
+        assert_eq!(mismatch.actual, "expr(val,op,val)");
+        assert_eq!(
+            mismatch.to_string(),
+            String::new()
+                + "expected shape `expr(val,op,expr(val))`, found `expr(val,op,val)`\n"
+                + "actual tree:\n"
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+        );
+    }
+}
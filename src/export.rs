@@ -0,0 +1,51 @@
+//! Conversions of the parse tree into other formats than the ascii tree.
+
+use std::fmt::Write;
+
+mod csv;
+mod edges;
+mod hooks;
+mod html;
+mod logfmt;
+mod paths;
+mod shape;
+#[cfg(feature = "termtree")]
+mod termtree;
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use self::csv::leaves_to_csv;
+pub use self::edges::{into_edge_list, NodeInfo};
+pub use self::hooks::{render_with_hooks, RenderHooks};
+pub use self::html::into_html_details;
+pub use self::logfmt::into_logfmt;
+pub use self::paths::into_path_list;
+pub use self::shape::shape_signature;
+pub(crate) use self::shape::write_shape;
+#[cfg(feature = "termtree")]
+pub use self::termtree::into_termtree;
+pub use self::xml::into_xml;
+#[cfg(feature = "yaml")]
+pub use self::yaml::into_yaml;
+
+/// Writes `text` double-quoted, escaping quotes, backslashes and control
+/// characters like JSON, so the value always stays on its line. This is
+/// valid in YAML and logfmt alike.
+fn write_quoted(output: &mut String, text: &str) {
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
@@ -0,0 +1,69 @@
+use crate::tree::ParseTree;
+use pest::iterators::Pairs;
+use std::fmt::Write;
+
+/// Writes `field` as CSV field, enclosed in double quotes with the
+/// double quotes in it doubled if it contains any of them, a comma or a
+/// line break.
+fn write_field(output: &mut String, field: &str) {
+    if field.contains(&['"', ',', '\n', '\r'][..]) {
+        output.push('"');
+        output.push_str(&field.replace('"', "\"\""));
+        output.push('"');
+    } else {
+        output.push_str(field);
+    }
+}
+
+/// Converts the leaves of the parsing result by pest into CSV, one leaf
+/// per row in traversal order, e.g. to import all tokens into a
+/// spreadsheet.
+///
+/// The first row is the header `rule,content,start,end`. The content is
+/// the matched text without surrounding whitespace and `start` and `end`
+/// are the byte offsets of the leaf's span in the input. Fields are
+/// quoted as described in RFC 4180. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let csv = pest_ascii_tree::leaves_to_csv(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     csv,
+///     String::new() + "rule,content,start,end\n" + "val,a,0,1\n" + "op,+,2,3\n" + "val,b,4,5\n"
+/// );
+/// ```
+pub fn leaves_to_csv<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::from("rule,content,start,end\n");
+    for leaf in ParseTree::new(pairs).iter().filter(|node| node.is_leaf()) {
+        write_field(&mut output, leaf.rule());
+        output.push(',');
+        write_field(&mut output, leaf.content());
+        let _ = writeln!(output, ",{},{}", leaf.span().start(), leaf.span().end());
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn csv() {
+        let csv =
+            leaves_to_csv(ExpressionParser::parse(Rule::lines, "{x,y}{\"q\"}\n{}\n").unwrap());
+        assert_eq!(
+            csv,
+            String::new()
+                + "rule,content,start,end\n"
+                + "block,\"{x,y}\",0,5\n"
+                + "block,\"{\"\"q\"\"}\",5,10\n"
+                + "block,{},11,13\n"
+        );
+    }
+}
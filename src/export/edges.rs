@@ -0,0 +1,84 @@
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+/// A node of the edge list returned by [`into_edge_list`].
+///
+/// [`into_edge_list`]: fn.into_edge_list.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// The index of the node in traversal order, starting at 0.
+    pub id: usize,
+    /// The name of the rule.
+    pub rule: String,
+    /// The matched text without surrounding whitespace.
+    pub content: String,
+}
+
+fn collect(
+    nodes: &[ParseTreeNode],
+    parent: Option<usize>,
+    infos: &mut Vec<NodeInfo>,
+    edges: &mut Vec<(usize, usize)>,
+) {
+    for node in nodes {
+        let id = infos.len();
+        infos.push(NodeInfo {
+            id,
+            rule: node.rule.clone(),
+            content: node.content().to_string(),
+        });
+        if let Some(parent) = parent {
+            edges.push((parent, id));
+        }
+        collect(&node.children, Some(id), infos, edges);
+    }
+}
+
+/// Converts the parsing result by pest into its nodes and the edges from
+/// every parent to its children, to be fed into any graph library.
+///
+/// The ids are assigned in traversal order, so `infos[id].id == id`, and
+/// an edge `(parent, child)` refers to these ids. Top-level pairs have no
+/// incoming edge. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let (infos, edges) = pest_ascii_tree::into_edge_list(
+///                          ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(infos[1].rule, "val");
+/// assert_eq!(edges, vec![(0, 1), (0, 2), (0, 3)]);
+/// ```
+pub fn into_edge_list<R>(pairs: Pairs<R>) -> (Vec<NodeInfo>, Vec<(usize, usize)>)
+where
+    R: pest::RuleType,
+{
+    let mut infos = Vec::new();
+    let mut edges = Vec::new();
+    collect(
+        &tree::build(pairs, &TreeConfig::default()),
+        None,
+        &mut infos,
+        &mut edges,
+    );
+
+    (infos, edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn edge_list() {
+        let (infos, edges) =
+            into_edge_list(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        let rules: Vec<_> = infos.iter().map(|info| info.rule.as_str()).collect();
+        assert_eq!(rules, ["expr", "val", "op", "expr", "val"]);
+        assert!(infos.iter().enumerate().all(|(i, info)| info.id == i));
+        assert_eq!(infos[3].content, "b");
+        assert_eq!(edges, [(0, 1), (0, 2), (0, 3), (3, 4)]);
+    }
+}
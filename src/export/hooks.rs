@@ -0,0 +1,165 @@
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+/// Callbacks of [`render_with_hooks`] for the structural boundaries of
+/// the parse tree. The strings they return are inserted into the output
+/// in traversal order.
+///
+/// All methods return an empty string by default, so only the needed
+/// ones have to be implemented. The depth of the top-level pairs is 0.
+///
+/// [`render_with_hooks`]: fn.render_with_hooks.html
+pub trait RenderHooks {
+    /// Called before the children of a node with children.
+    fn on_enter(&mut self, rule: &str, depth: usize) -> String {
+        let _ = (rule, depth);
+        String::new()
+    }
+
+    /// Called for a node without children, with its matched text without
+    /// surrounding whitespace.
+    fn on_leaf(&mut self, rule: &str, content: &str, depth: usize) -> String {
+        let _ = (rule, content, depth);
+        String::new()
+    }
+
+    /// Called after the children of a node with children.
+    fn on_exit(&mut self, depth: usize) -> String {
+        let _ = depth;
+        String::new()
+    }
+}
+
+fn walk(output: &mut String, nodes: &[ParseTreeNode], hooks: &mut dyn RenderHooks, depth: usize) {
+    for node in nodes {
+        if node.is_leaf() {
+            output.push_str(&hooks.on_leaf(&node.rule, node.content(), depth));
+        } else {
+            output.push_str(&hooks.on_enter(&node.rule, depth));
+            walk(output, &node.children, hooks, depth + 1);
+            output.push_str(&hooks.on_exit(depth));
+        }
+    }
+}
+
+/// Walks the parsing result by pest, joining the strings returned by the
+/// `hooks` at every node into the output.
+///
+/// This allows custom output formats, like DOT, without reimplementing
+/// the traversal. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// struct Brackets;
+///
+/// impl pest_ascii_tree::RenderHooks for Brackets {
+///     fn on_enter(&mut self, rule: &str, _depth: usize) -> String {
+///         format!("{}(", rule)
+///     }
+///
+///     fn on_leaf(&mut self, _rule: &str, content: &str, _depth: usize) -> String {
+///         format!("{} ", content)
+///     }
+///
+///     fn on_exit(&mut self, _depth: usize) -> String {
+///         String::from(")")
+///     }
+/// }
+///
+/// let output = pest_ascii_tree::render_with_hooks(
+///                  ExpressionParser::parse(Rule::expr, "a + b")?, &mut Brackets);
+/// assert_eq!(output, "expr(a + b )");
+/// ```
+pub fn render_with_hooks<R>(pairs: Pairs<R>, hooks: &mut dyn RenderHooks) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    walk(
+        &mut output,
+        &tree::build(pairs, &TreeConfig::default()),
+        hooks,
+        0,
+    );
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    /// Renders the nodes as indented XML-like tags, counting the leaves.
+    #[derive(Default)]
+    struct Tags {
+        leaves: usize,
+        /// The rules and depths of the entered nodes, the innermost last.
+        open: Vec<(String, usize)>,
+    }
+
+    impl RenderHooks for Tags {
+        fn on_enter(&mut self, rule: &str, depth: usize) -> String {
+            self.open.push((rule.to_string(), depth));
+            format!("{}<{}>\n", "  ".repeat(depth), rule)
+        }
+
+        fn on_leaf(&mut self, rule: &str, content: &str, depth: usize) -> String {
+            self.leaves += 1;
+            format!("{}<{} text={:?}/>\n", "  ".repeat(depth), rule, content)
+        }
+
+        fn on_exit(&mut self, depth: usize) -> String {
+            let (rule, entered) = self.open.pop().expect("exit without enter");
+            assert_eq!(depth, entered);
+            format!("{}</{}>\n", "  ".repeat(depth), rule)
+        }
+    }
+
+    #[test]
+    fn hooks() {
+        let mut hooks = Tags::default();
+        let output = render_with_hooks(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &mut hooks,
+        );
+        assert_eq!(
+            output,
+            String::new()
+                + "<expr>\n"
+                + "  <val text=\"a\"/>\n"
+                + "  <op text=\"+\"/>\n"
+                + "  <expr>\n"
+                + "    <val text=\"b\"/>\n"
+                + "  </expr>\n"
+                + "</expr>\n"
+        );
+        assert_eq!(hooks.leaves, 3);
+        assert!(hooks.open.is_empty());
+
+        let mut hooks = Tags::default();
+        let output = render_with_hooks(
+            ExpressionParser::parse(Rule::expr, "((a) + b) - c").unwrap(),
+            &mut hooks,
+        );
+        assert_eq!(
+            output,
+            String::new()
+                + "<expr>\n"
+                + "  <expr>\n"
+                + "    <expr>\n"
+                + "      <val text=\"a\"/>\n"
+                + "    </expr>\n"
+                + "    <op text=\"+\"/>\n"
+                + "    <val text=\"b\"/>\n"
+                + "  </expr>\n"
+                + "  <op text=\"-\"/>\n"
+                + "  <val text=\"c\"/>\n"
+                + "</expr>\n"
+        );
+        assert_eq!(hooks.leaves, 5);
+        assert!(hooks.open.is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use pest::iterators::Pairs;
+
+/// Escapes the characters with a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn write_tree(output: &mut String, tree: &ascii_tree::Tree, indent: usize) {
+    let (summary, children) = match tree {
+        ascii_tree::Tree::Node(title, children) => (title.clone(), &children[..]),
+        ascii_tree::Tree::Leaf(lines) => (lines.join(" "), &[][..]),
+    };
+
+    output.push_str(&"  ".repeat(indent));
+    output.push_str("<details><summary>");
+    output.push_str(&escape_html(&summary));
+    output.push_str("</summary>");
+    if !children.is_empty() {
+        output.push('\n');
+        for child in children {
+            write_tree(output, child, indent + 1);
+        }
+        output.push_str(&"  ".repeat(indent));
+    }
+    output.push_str("</details>\n");
+}
+
+/// Converts the parsing result by pest into nested HTML `<details>`
+/// elements, so readers can expand only the parts they care about.
+///
+/// The summary of every element is labeled like the line in
+/// [`into_ascii_tree`], so leaves show their escaped content. Rule names
+/// and content are HTML-escaped. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let html = pest_ascii_tree::into_html_details(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     html,
+///     String::new()
+///         + "<details><summary>expr</summary>\n"
+///         + "  <details><summary>val &quot;a&quot;</summary></details>\n"
+///         + "  <details><summary>op &quot;+&quot;</summary></details>\n"
+///         + "  <details><summary>val &quot;b&quot;</summary></details>\n"
+///         + "</details>\n"
+/// );
+/// ```
+///
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+pub fn into_html_details<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    for tree in crate::convert_pairs(pairs) {
+        write_tree(&mut output, &tree, 0);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn html_details() {
+        let html = into_html_details(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        assert_eq!(
+            html,
+            String::new()
+                + "<details><summary>expr</summary>\n"
+                + "  <details><summary>val &quot;a&quot;</summary></details>\n"
+                + "  <details><summary>op &quot;+&quot;</summary></details>\n"
+                + "  <details><summary>expr</summary>\n"
+                + "    <details><summary>val &quot;b&quot;</summary></details>\n"
+                + "  </details>\n"
+                + "</details>\n"
+        );
+
+        assert_eq!(escape_html("<a & 'b'>"), "&lt;a &amp; &#39;b&#39;&gt;");
+    }
+}
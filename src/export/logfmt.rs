@@ -0,0 +1,86 @@
+use super::write_quoted;
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+use std::fmt::Write;
+
+/// Writes `text` as logfmt value, quoted if it contains anything but
+/// plain word characters.
+fn write_value(output: &mut String, text: &str) {
+    let plain = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+    if plain {
+        output.push_str(text);
+    } else {
+        write_quoted(output, text);
+    }
+}
+
+fn write_nodes(output: &mut String, nodes: &[ParseTreeNode], depth: usize) {
+    for node in nodes {
+        let _ = write!(output, "depth={} rule=", depth);
+        write_value(output, &node.rule);
+        output.push_str(" text=");
+        write_quoted(output, node.content());
+        output.push('\n');
+
+        write_nodes(output, &node.children, depth + 1);
+    }
+}
+
+/// Converts the parsing result by pest into logfmt lines, one node per
+/// line in traversal order.
+///
+/// Every line has the fields `depth`, starting at 0 for the top-level
+/// pairs, `rule` and `text`. The text is always double-quoted and
+/// escaped, so spaces or newlines in the matched text don't break the
+/// line. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let logfmt = pest_ascii_tree::into_logfmt(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     logfmt,
+///     String::new()
+///         + "depth=0 rule=expr text=\"a + b\"\n"
+///         + "depth=1 rule=val text=\"a\"\n"
+///         + "depth=1 rule=op text=\"+\"\n"
+///         + "depth=1 rule=val text=\"b\"\n"
+/// );
+/// ```
+pub fn into_logfmt<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    write_nodes(&mut output, &tree::build(pairs, &TreeConfig::default()), 0);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn logfmt() {
+        let logfmt = into_logfmt(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        assert_eq!(
+            logfmt,
+            String::new()
+                + "depth=0 rule=expr text=\"a + (b)\"\n"
+                + "depth=1 rule=val text=\"a\"\n"
+                + "depth=1 rule=op text=\"+\"\n"
+                + "depth=1 rule=expr text=\"b\"\n"
+                + "depth=2 rule=val text=\"b\"\n"
+        );
+
+        let mut escaped = String::new();
+        write_quoted(&mut escaped, "a \"b\"\n\\");
+        assert_eq!(escaped, "\"a \\\"b\\\"\\n\\\\\"");
+    }
+}
@@ -0,0 +1,81 @@
+use super::write_quoted;
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+fn write_nodes(output: &mut String, nodes: &[ParseTreeNode], path: &mut String) {
+    for node in nodes {
+        let parent_len = path.len();
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(&node.rule);
+
+        output.push_str(path);
+        if node.is_leaf() {
+            output.push(' ');
+            write_quoted(output, node.content());
+        }
+        output.push('\n');
+
+        write_nodes(output, &node.children, path);
+        path.truncate(parent_len);
+    }
+}
+
+/// Converts the parsing result by pest into a flat list of paths, one
+/// node per line in traversal order like the output of `find`.
+///
+/// Every line holds the rule names from the top-level pair down to the
+/// node, separated by `/`. Leaves are followed by their double-quoted
+/// and escaped content, so every line can be matched by `grep`. The `EOI`
+/// rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let paths = pest_ascii_tree::into_path_list(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     paths,
+///     String::new()
+///         + "expr\n"
+///         + "expr/val \"a\"\n"
+///         + "expr/op \"+\"\n"
+///         + "expr/val \"b\"\n"
+/// );
+/// ```
+pub fn into_path_list<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    write_nodes(
+        &mut output,
+        &tree::build(pairs, &TreeConfig::default()),
+        &mut String::new(),
+    );
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn path_list() {
+        let paths = into_path_list(ExpressionParser::parse(Rule::statement, "a + (b c);").unwrap());
+        assert_eq!(
+            paths,
+            String::new()
+                + "statement\n"
+                + "statement/expr\n"
+                + "statement/expr/val \"a\"\n"
+                + "statement/expr/op \"+\"\n"
+                + "statement/expr/expr\n"
+                + "statement/expr/expr/val \"b c\"\n"
+                + "statement/terminator \";\"\n"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+/// Writes the rule names of the `nodes`, separated by `,`, with the
+/// children of every node in parentheses behind its name.
+pub(crate) fn write_shape(output: &mut String, nodes: &[ParseTreeNode]) {
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        output.push_str(&node.rule);
+        if !node.children.is_empty() {
+            output.push('(');
+            write_shape(output, &node.children);
+            output.push(')');
+        }
+    }
+}
+
+/// Converts the parsing result by pest into a compact signature of the
+/// shape of its tree, without any content or whitespace, like
+/// `expr(val,op,expr(val,op,val))`.
+///
+/// Two inputs parsed into trees of the same shape have equal signatures,
+/// so the signatures can be compared or used as keys to bucket inputs by
+/// structure. Multiple top-level pairs are separated by `,`. The `EOI`
+/// rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let signature = pest_ascii_tree::shape_signature(
+///                     ExpressionParser::parse(Rule::expr, "a + (b - c)")?);
+/// assert_eq!(signature, "expr(val,op,expr(val,op,val))");
+/// ```
+pub fn shape_signature<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    write_shape(&mut output, &tree::build(pairs, &TreeConfig::default()));
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn shape_signature() {
+        let signature =
+            |rule, input| super::shape_signature(ExpressionParser::parse(rule, input).unwrap());
+        assert_eq!(
+            signature(Rule::expr, "a + (b - c)"),
+            "expr(val,op,expr(val,op,val))"
+        );
+        assert_eq!(
+            signature(Rule::expr, "a + (b - c)"),
+            signature(Rule::expr, "xy - ( 1 + 2 )")
+        );
+        assert_ne!(
+            signature(Rule::expr, "a + (b - c)"),
+            signature(Rule::expr, "a + b - c")
+        );
+        assert_eq!(signature(Rule::expr_root, "a + b"), "val,op,val");
+    }
+}
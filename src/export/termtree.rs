@@ -0,0 +1,62 @@
+use pest::iterators::Pairs;
+
+fn into_termtree_node(tree: ascii_tree::Tree) -> ::termtree::Tree<String> {
+    match tree {
+        ascii_tree::Tree::Node(title, children) => {
+            ::termtree::Tree::new(title).with_leaves(children.into_iter().map(into_termtree_node))
+        }
+        ascii_tree::Tree::Leaf(lines) => ::termtree::Tree::new(lines.join("\n")),
+    }
+}
+
+/// Converts the parsing result by pest into a [`termtree::Tree`], to be
+/// rendered with termtree's glyphs and options.
+///
+/// The nodes are labeled like in [`into_ascii_tree`]. If there is more
+/// than one top-level pair, they become the leaves of a root with an
+/// empty label.
+///
+/// # Examples
+/// ```ignore
+/// let tree = pest_ascii_tree::into_termtree(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// print!("{}", tree);
+/// ```
+///
+/// [`termtree::Tree`]: https://docs.rs/termtree/0.5/termtree/struct.Tree.html
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+pub fn into_termtree<R>(pairs: Pairs<R>) -> ::termtree::Tree<String>
+where
+    R: pest::RuleType,
+{
+    let mut nodes = crate::convert_pairs(pairs);
+
+    if nodes.len() == 1 {
+        into_termtree_node(nodes.remove(0))
+    } else {
+        ::termtree::Tree::new(String::new()).with_leaves(nodes.into_iter().map(into_termtree_node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn termtree() {
+        let tree = into_termtree(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        assert_eq!(
+            tree.to_string(),
+            String::new()
+                + "expr\n"
+                + "├── val \"a\"\n"
+                + "├── op \"+\"\n"
+                + "└── expr\n"
+                + "    └── val \"b\"\n"
+        );
+
+        let tree = into_termtree(ExpressionParser::parse(Rule::expr_root, "a + b").unwrap());
+        assert_eq!(tree.leaves.len(), 3);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+/// Turns the rule name into a valid XML element name by replacing invalid
+/// characters with `_`. Names not starting with a letter or `_`, or
+/// starting with the reserved `xml` in any case, are prefixed by `_`.
+fn element_name(rule: &str) -> String {
+    let mut name: String = rule
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let reserved = matches!(name.get(..3), Some(start) if start.eq_ignore_ascii_case("xml"));
+    if reserved || !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+
+    name
+}
+
+/// Escapes the text for XML. Control characters XML can't represent are
+/// replaced by `�`.
+fn write_text(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\t' | '\n' | '\r' => output.push(c),
+            c if c.is_control() => output.push('\u{FFFD}'),
+            c => output.push(c),
+        }
+    }
+}
+
+fn write_nodes(output: &mut String, nodes: &[ParseTreeNode], indent: usize) {
+    for node in nodes {
+        let name = element_name(&node.rule);
+        output.push_str(&"  ".repeat(indent));
+        output.push('<');
+        output.push_str(&name);
+        output.push('>');
+
+        if node.is_leaf() {
+            write_text(output, node.content());
+        } else {
+            output.push('\n');
+            write_nodes(output, &node.children, indent + 1);
+            output.push_str(&"  ".repeat(indent));
+        }
+
+        output.push_str("</");
+        output.push_str(&name);
+        output.push_str(">\n");
+    }
+}
+
+/// Converts the parsing result by pest into XML, with an element named
+/// after the rule for every node and the content of leaves as text.
+///
+/// Rule names are turned into valid element names, replacing invalid
+/// characters with `_`. If there isn't exactly one top-level pair, they
+/// become the children of a `pairs` element, so the output is always a
+/// well-formed document. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let xml = pest_ascii_tree::into_xml(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     xml,
+///     String::new()
+///         + "<expr>\n"
+///         + "  <val>a</val>\n"
+///         + "  <op>+</op>\n"
+///         + "  <val>b</val>\n"
+///         + "</expr>\n"
+/// );
+/// ```
+pub fn into_xml<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let nodes = tree::build(pairs, &TreeConfig::default());
+
+    let mut output = String::new();
+    if nodes.len() == 1 {
+        write_nodes(&mut output, &nodes, 0);
+    } else {
+        output.push_str("<pairs>\n");
+        write_nodes(&mut output, &nodes, 1);
+        output.push_str("</pairs>\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    /// Checks that all tags are balanced with valid, unreserved names,
+    /// there is a single root element and the text between the tags
+    /// contains no `>` and only `&` starting a known entity.
+    fn is_well_formed(xml: &str) -> bool {
+        let is_valid_text = |text: &str| {
+            !text.contains('>')
+                && text.match_indices('&').all(|(i, _)| {
+                    ["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"]
+                        .iter()
+                        .any(|entity| text[i..].starts_with(entity))
+                })
+        };
+        let is_valid_name = |name: &str| {
+            name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+                && !matches!(name.get(..3), Some(start) if start.eq_ignore_ascii_case("xml"))
+        };
+
+        let mut open = Vec::new();
+        let mut roots = 0;
+        let mut rest = xml;
+        while let Some(start) = rest.find('<') {
+            let end = match rest[start..].find('>') {
+                Some(end) => start + end,
+                None => return false,
+            };
+            if !is_valid_text(&rest[..start]) {
+                return false;
+            }
+
+            let tag = &rest[start + 1..end];
+            if let Some(name) = tag.strip_prefix('/') {
+                if open.pop() != Some(name) {
+                    return false;
+                }
+            } else {
+                if open.is_empty() {
+                    roots += 1;
+                }
+                let (name, closed) = match tag.strip_suffix('/') {
+                    Some(name) => (name, true),
+                    None => (tag, false),
+                };
+                if !is_valid_name(name) {
+                    return false;
+                }
+                if !closed {
+                    open.push(name);
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+
+        open.is_empty() && roots == 1 && rest.trim().is_empty()
+    }
+
+    /// Rules whose names aren't valid XML element names.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    enum OddRule {
+        Document,
+        Text,
+    }
+
+    impl std::fmt::Debug for OddRule {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str(match self {
+                OddRule::Document => "XML document",
+                OddRule::Text => "1 text",
+            })
+        }
+    }
+
+    #[test]
+    fn xml() {
+        let xml = into_xml(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        assert_eq!(
+            xml,
+            String::new()
+                + "<expr>\n"
+                + "  <val>a</val>\n"
+                + "  <op>+</op>\n"
+                + "  <expr>\n"
+                + "    <val>b</val>\n"
+                + "  </expr>\n"
+                + "</expr>\n"
+        );
+        assert!(is_well_formed(&xml));
+
+        let xml = into_xml(ExpressionParser::parse(Rule::expr_root, "a + b").unwrap());
+        assert!(xml.starts_with("<pairs>\n  <val>a</val>\n"));
+        assert!(is_well_formed(&xml));
+
+        let xml = into_xml(ExpressionParser::parse(Rule::block, "{<&>}").unwrap());
+        assert_eq!(xml, "<block>{&lt;&amp;&gt;}</block>\n");
+        assert!(is_well_formed(&xml));
+
+        let pairs = pest::state("a < b && c > d", |state| {
+            state.rule(OddRule::Document, |state| {
+                state.rule(OddRule::Text, |state| state.match_string("a < b && c > d"))
+            })
+        })
+        .unwrap();
+        let xml = into_xml(pairs);
+        assert_eq!(
+            xml,
+            String::new()
+                + "<_XML_document>\n"
+                + "  <_1_text>a &lt; b &amp;&amp; c &gt; d</_1_text>\n"
+                + "</_XML_document>\n"
+        );
+        assert!(is_well_formed(&xml));
+
+        assert!(!is_well_formed("<a>b & c</a>"));
+        assert!(!is_well_formed("<a>b > c</a>"));
+        assert!(!is_well_formed("<xml>b</xml>"));
+        assert!(!is_well_formed("<a><b/></a><c/>"));
+        assert!(is_well_formed("<a><b/>&amp;</a>"));
+    }
+
+    #[test]
+    fn element_names() {
+        assert_eq!(element_name("a b\"c"), "a_b_c");
+        assert_eq!(element_name("1st"), "_1st");
+        assert_eq!(element_name("xml"), "_xml");
+        assert_eq!(element_name("XmlDecl"), "_XmlDecl");
+        assert_eq!(element_name("x_ml"), "x_ml");
+        assert_eq!(element_name("xm"), "xm");
+    }
+}
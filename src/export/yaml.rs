@@ -0,0 +1,126 @@
+use super::write_quoted;
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+
+/// Words a plain YAML scalar would be read as boolean or null.
+const KEYWORDS: &[&str] = &["true", "false", "null", "yes", "no", "on", "off", "y", "n"];
+
+/// Whether `text` can be written as plain YAML scalar without changing
+/// its meaning.
+fn is_plain(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        && !KEYWORDS
+            .iter()
+            .any(|keyword| text.eq_ignore_ascii_case(keyword))
+}
+
+fn write_scalar(output: &mut String, text: &str) {
+    if is_plain(text) {
+        output.push_str(text);
+    } else {
+        write_quoted(output, text);
+    }
+}
+
+fn write_nodes(output: &mut String, nodes: &[ParseTreeNode], indent: usize) {
+    for node in nodes {
+        output.push_str(&" ".repeat(indent));
+        output.push_str("- rule: ");
+        write_scalar(output, &node.rule);
+        output.push('\n');
+
+        output.push_str(&" ".repeat(indent + 2));
+        if node.is_leaf() {
+            output.push_str("text: ");
+            write_quoted(output, node.content());
+            output.push('\n');
+        } else {
+            output.push_str("children:\n");
+            write_nodes(output, &node.children, indent + 4);
+        }
+    }
+}
+
+/// Converts the parsing result by pest into a YAML sequence of the
+/// top-level nodes.
+///
+/// Every node is a mapping with its `rule` and either the `children` or,
+/// for leaves, the matched `text`. The text is always double-quoted and
+/// escaped, so special characters, colons or leading spaces keep their
+/// meaning. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let yaml = pest_ascii_tree::into_yaml(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert_eq!(
+///     yaml,
+///     String::new()
+///         + "- rule: expr\n"
+///         + "  children:\n"
+///         + "    - rule: val\n"
+///         + "      text: \"a\"\n"
+///         + "    - rule: op\n"
+///         + "      text: \"+\"\n"
+///         + "    - rule: val\n"
+///         + "      text: \"b\"\n"
+/// );
+/// ```
+pub fn into_yaml<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let nodes = tree::build(pairs, &TreeConfig::default());
+
+    if nodes.is_empty() {
+        return String::from("[]\n");
+    }
+
+    let mut output = String::new();
+    write_nodes(&mut output, &nodes, 0);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn yaml() {
+        let yaml = into_yaml(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap());
+        assert_eq!(
+            yaml,
+            String::new()
+                + "- rule: expr\n"
+                + "  children:\n"
+                + "    - rule: val\n"
+                + "      text: \"a\"\n"
+                + "    - rule: op\n"
+                + "      text: \"+\"\n"
+                + "    - rule: expr\n"
+                + "      children:\n"
+                + "        - rule: val\n"
+                + "          text: \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        let mut output = String::new();
+        write_quoted(&mut output, " key: \"value\"\\\n\u{1}");
+        assert_eq!(output, "\" key: \\\"value\\\"\\\\\\n\\u0001\"");
+
+        assert!(is_plain("expr_root"));
+        assert!(!is_plain("a: b"));
+        assert!(!is_plain("-x"));
+        assert!(!is_plain("1"));
+        assert!(!is_plain("Null"));
+        assert!(!is_plain(""));
+    }
+}
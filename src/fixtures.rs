@@ -0,0 +1,91 @@
+//! Grammars and helpers shared by the tests, so options can be tested
+//! against other shapes of trees than the ones of `expression.pest`.
+
+use crate::config::TreeConfig;
+use pest::Parser;
+
+/// A JSON-like grammar with objects, arrays and scalar leaves. Numbers
+/// may be written in hex, like `0xff`.
+pub(crate) mod json {
+    #[derive(Parser)]
+    #[grammar = "json.pest"]
+    pub(crate) struct JsonParser;
+}
+
+/// A grammar of nested parenthesized lists, like `(12, ab, (3))`, whose
+/// numbers consist of one `digit` leaf per character.
+pub(crate) mod list {
+    #[derive(Parser)]
+    #[grammar = "list.pest"]
+    pub(crate) struct ListParser;
+}
+
+/// Parses the `input` by the parser `P` starting at the `rule` and
+/// renders it with the `config`. Panics if parsing fails.
+pub(crate) fn render<P, R>(rule: R, input: &str, config: &TreeConfig) -> String
+where
+    P: Parser<R>,
+    R: pest::RuleType,
+{
+    let pairs =
+        P::parse(rule, input).unwrap_or_else(|e| panic!("failed to parse {:?}:\n{}", input, e));
+    crate::into_ascii_tree_with_config(pairs, config).unwrap()
+}
+
+/// Like [`render`], but with the default options.
+pub(crate) fn render_default<P, R>(rule: R, input: &str) -> String
+where
+    P: Parser<R>,
+    R: pest::RuleType,
+{
+    render::<P, R>(rule, input, &TreeConfig::default())
+}
+
+mod tests {
+    use super::json::{self, JsonParser};
+    use super::list::{self, ListParser};
+    use super::*;
+
+    #[test]
+    fn json() {
+        let result =
+            render_default::<JsonParser, _>(json::Rule::json, "{\"a\": [1, 0xff], \"b\": null}");
+        assert_eq!(
+            result,
+            String::new()
+                + " object\n"
+                + " ├─ pair\n"
+                + " │  ├─ string\n"
+                + " │  │  └─ text \"a\"\n"
+                + " │  └─ array\n"
+                + " │     ├─ number \"1\"\n"
+                + " │     └─ number \"0xff\"\n"
+                + " └─ pair\n"
+                + "    ├─ string\n"
+                + "    │  └─ text \"b\"\n"
+                + "    └─ null \"null\"\n"
+        );
+    }
+
+    #[test]
+    fn list() {
+        let config = TreeConfig {
+            merge_same_rule_leaves: true,
+            merge_separator: String::new(),
+            ..Default::default()
+        };
+
+        let result = render::<ListParser, _>(list::Rule::list, "(12, ab, (3))", &config);
+        assert_eq!(
+            result,
+            String::new()
+                + " list\n"
+                + " ├─ number\n"
+                + " │  └─ digit \"12\"\n"
+                + " ├─ word \"ab\"\n"
+                + " └─ list\n"
+                + "    └─ number\n"
+                + "       └─ digit \"3\"\n"
+        );
+    }
+}
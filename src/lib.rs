@@ -30,56 +30,114 @@
 //!
 //! Please, that the `EOI` rule is skipped.
 //!
+//! The output is deterministic: the same pairs and options always result
+//! in the same bytes, so it can be used for snapshot tests. Options kept
+//! in hash maps or sets never influence the order of the output.
+//!
+//! With the `cli` feature enabled, the crate also provides the
+//! `pest-ascii-tree` binary, which loads a grammar at runtime and prints
+//! the ascii tree of parsing the standard input:
+//!
+//! ```text
+//! pest-ascii-tree expression.pest expr < input.txt
+//! ```
+//!
 //! [`ascii_tree`]: https://docs.rs/ascii_tree/0.1.1/ascii_tree/index.html
 
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
 extern crate ascii_tree;
 extern crate escape_string;
 extern crate pest;
+#[cfg(test)]
 #[macro_use]
 extern crate pest_derive;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "termtree")]
+extern crate termtree;
+#[cfg(feature = "unicode-width")]
+extern crate unicode_width;
 
-use pest::{error::Error, iterators::Pairs};
-
-fn into_ascii_tree_nodes<R>(mut pairs: Pairs<R>) -> Vec<ascii_tree::Tree>
-where
-    R: pest::RuleType,
-{
-    let mut vec = Vec::new();
-
-    while let Some(pair) = pairs.next() {
-        let pair_content = pair.as_span().as_str().trim();
-        let pair_rule = pair.as_rule();
-        let inner_pairs = into_ascii_tree_nodes(pair.into_inner());
+mod config;
+mod diff;
+mod error;
+mod expect;
+mod export;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "regex")]
+mod matching;
+mod render;
+mod report;
+mod stats;
+mod stream;
+mod tree;
+mod width;
 
-        let rule_name = format!("{:?}", pair_rule);
-        if rule_name == "EOI" {
-            continue;
-        }
+pub use config::{ForestOrder, MultiRootStyle, NameCase, NodeContentMode, TreeConfig, TruncateAt};
+pub use diff::{highlight_changes, tree_diff};
+pub use error::TreeError;
+pub use expect::{check_tree, render_expecting, ShapeMismatch, TreeExpectation};
+#[cfg(feature = "termtree")]
+pub use export::into_termtree;
+#[cfg(feature = "yaml")]
+pub use export::into_yaml;
+pub use export::{
+    into_edge_list, into_html_details, into_logfmt, into_path_list, into_xml, leaves_to_csv,
+    render_with_hooks, shape_signature, NodeInfo, RenderHooks,
+};
+#[cfg(feature = "regex")]
+pub use matching::into_ascii_tree_matching;
+pub use report::{render_report, RenderReport, Warning};
+pub use stats::{into_level_summary, tree_stats, TreeStats};
+pub use stream::{render_roots_streaming, write_ascii_tree_streaming};
+pub use tree::{Nodes, ParseTree, ParseTreeNode, Status};
 
-        let node;
-        if inner_pairs.is_empty() {
-            let leaf = format!(
-                "{:?} \"{}\"",
-                pair_rule,
-                escape_string::escape(pair_content)
-            );
-            node = ascii_tree::Tree::Leaf(vec![leaf]);
-        } else {
-            node = ascii_tree::Tree::Node(rule_name, inner_pairs);
-        }
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
-        vec.push(node);
-    }
+use pest::{
+    error::Error,
+    iterators::{Pair, Pairs, Tokens},
+};
 
-    vec
+/// Converts the parsing result by pest into [`ascii_tree::Tree`] nodes
+/// without rendering them.
+///
+/// Every top-level pair becomes one element of the returned `Vec`.
+/// Pairs with inner pairs become a `Tree::Node` named after the rule,
+/// all others a `Tree::Leaf` holding the rule and the escaped content,
+/// like `val "a"`. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let nodes = pest_ascii_tree::convert_pairs(ExpressionParser::parse(Rule::expr, "a + b")?);
+/// let mut output = String::new();
+/// ascii_tree::write_tree(&mut output, &ascii_tree::Tree::Node("input".into(), nodes))?;
+/// ```
+///
+/// [`ascii_tree::Tree`]: https://docs.rs/ascii_tree/0.1.1/ascii_tree/enum.Tree.html
+pub fn convert_pairs<R>(pairs: Pairs<R>) -> Vec<ascii_tree::Tree>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    tree::build(pairs, &config)
+        .iter()
+        .map(|node| render::into_ascii_tree_node(node, &config))
+        .collect()
 }
 
 /// Formats the parsing result by pest into an ascii_tree
 /// stored in a [`String`].
 ///
 /// # Error
-/// If the internal call to [`ascii_tree::write_tree`] failed, the error
-/// variant is passed to the caller.
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
 ///
 /// # Examples
 /// ```ignore
@@ -107,195 +165,3425 @@ where
 /// ```
 ///
 /// [`String`]: https://doc.rust-lang.org/nightly/alloc/string/struct.String.html
-/// [`ascii_tree::write_tree`]: https://docs.rs/ascii_tree/0.1.1/ascii_tree/fn.write_tree.html
 pub fn into_ascii_tree<R>(pairs: Pairs<R>) -> Result<String, std::fmt::Error>
 where
     R: pest::RuleType,
 {
-    let nodes = into_ascii_tree_nodes(pairs);
+    let config = TreeConfig::default();
+    render::render(&tree::build(pairs, &config), &config)
+}
 
-    let mut output = String::new();
+/// Formats the parsing result by pest into an ascii_tree using the
+/// options set in the `config`.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let config = pest_ascii_tree::TreeConfig {
+///     collapse_unary_chains: true,
+///     ..Default::default()
+/// };
+/// let result = pest_ascii_tree::into_ascii_tree_with_config(
+///                  ExpressionParser::parse(Rule::expr, "((a))")?, &config)?;
+/// assert_eq!(result, " expr>expr>expr>val \"a\"\n");
+/// ```
+pub fn into_ascii_tree_with_config<R>(
+    pairs: Pairs<R>,
+    config: &TreeConfig,
+) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    Ok(render::render(&tree::build(pairs, config), config)?)
+}
 
-    match nodes.len() {
-        0 => {}
-        1 => {
-            ascii_tree::write_tree(&mut output, nodes.first().unwrap())?;
-        }
-        _ => {
-            let root = ascii_tree::Tree::Node(String::new(), nodes);
-            ascii_tree::write_tree(&mut output, &root)?;
+/// Formats the parsing result by pest into an upside-down ascii_tree,
+/// with the leaves at the top and the root at the bottom.
+///
+/// Reading from top to bottom, the children of every node come in source
+/// order right above their parent, which follows the order in which the
+/// nodes are reduced.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_inverted(
+///                  ExpressionParser::parse(Rule::expr, "a + (b)")?)?;
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " ┌─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " │  ┌─ val \"b\"\n"
+///         + " ├─ expr\n"
+///         + " expr\n"
+/// );
+/// ```
+pub fn into_ascii_tree_inverted<R>(pairs: Pairs<R>) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let renderer = render::Renderer {
+        inverted: true,
+        ..render::Renderer::new(&config)
+    };
+    Ok(renderer.render(&tree::build(pairs, &config))?)
+}
 
-            if output.starts_with(" \n") {
-                output = output.split_off(2);
-            }
-        }
+/// Formats the parsing result by pest into an ascii_tree, escaping the
+/// content of every leaf with `escape` instead of the default escaping.
+///
+/// The escaper can e.g. redact secrets or mask numbers. Options
+/// truncating the content are applied to its result.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_with_escaper(
+///                  ExpressionParser::parse(Rule::expr, "a + 42")?,
+///                  |content| content.replace(|c: char| c.is_ascii_digit(), "#"));
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"##\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_with_escaper<R, F>(pairs: Pairs<R>, escape: F) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+    F: Fn(&str) -> String,
+{
+    let config = TreeConfig::default();
+    let renderer = render::Renderer {
+        escape: &escape,
+        ..render::Renderer::new(&config)
     };
+    Ok(renderer.render(&tree::build(pairs, &config))?)
+}
 
-    Ok(output)
+/// Formats the parsing result by pest into an ascii_tree, appending the
+/// suffix returned by `annotate` for a pair in brackets behind its node.
+///
+/// The labels stay the same otherwise. Pairs `annotate` returns `None`
+/// for are shown without annotation.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_annotated(
+///                  ExpressionParser::parse(Rule::expr, "a + 42")?,
+///                  |pair| pair.as_str().parse::<i64>().ok().map(|n| format!("{:#x}", n)));
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"42\" [0x2a]\n"
+/// );
+/// ```
+pub fn into_ascii_tree_annotated<R, F>(pairs: Pairs<R>, annotate: F) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+    F: Fn(&Pair<R>) -> Option<String>,
+{
+    let config = TreeConfig::default();
+    Ok(render::render(
+        &tree::build_annotated(pairs, &config, &annotate),
+        &config,
+    )?)
 }
 
-/// Prints the result returned by your pest Parser as an ascii tree.
+/// Formats the parsing result by pest into an ascii_tree, showing the
+/// [`Status`] returned by `status` for a pair in front of its node.
 ///
-/// # Errors
-/// In case of an parsing error, the error is printed.
-/// In case of a formating error, the error is printed.
+/// The statuses are shown as `✅`, `⚠️` and `❌`, e.g. for the results
+/// of validating the parse. The labels stay the same otherwise.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
 ///
 /// # Examples
 /// ```ignore
-/// pest_ascii_tree::print_ascii_tree(
-///                     ExpressionParser::parse(Rule::expr,
-///                                             "(u + (v + w)) + (x + y) + z"));
+/// use pest_ascii_tree::Status;
+///
+/// let result = pest_ascii_tree::into_ascii_tree_with_status(
+///                  ExpressionParser::parse(Rule::expr, "a + 42")?,
+///                  |pair| match pair.as_str().parse::<i64>() {
+///                      Ok(n) if n > 9 => Status::Warn,
+///                      _ => Status::Ok,
+///                  });
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " ✅ expr\n"
+///         + " ├─ ✅ val \"a\"\n"
+///         + " ├─ ✅ op \"+\"\n"
+///         + " └─ ⚠️ val \"42\"\n"
+/// );
 /// ```
 ///
-/// will result in the output
+/// [`Status`]: enum.Status.html
+pub fn into_ascii_tree_with_status<R, F>(pairs: Pairs<R>, status: F) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+    F: Fn(&Pair<R>) -> Status,
+{
+    let config = TreeConfig::default();
+    Ok(render::render(
+        &tree::build_with_status(pairs, &config, &status),
+        &config,
+    )?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree, with the
+/// siblings at every level sorted by `cmp`.
 ///
-/// <pre>
-///  expr
-///  ├─ expr
-///  │  ├─ val "u"
-///  │  ├─ op "+"
-///  │  └─ expr
-///  │     ├─ val "v"
-///  │     ├─ op "+"
-///  │     └─ val "w"
-///  ├─ op "+"
-///  ├─ expr
-///  │  ├─ val "x"
-///  │  ├─ op "+"
-///  │  └─ val "y"
-///  ├─ op "+"
-///  └─ val "z"
-/// </pre>
+/// The sort is stable, so siblings comparing equal keep the order of the
+/// input.
 ///
-pub fn print_ascii_tree<R>(parsing_result: Result<Pairs<R>, Error<R>>)
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_sorted_by(
+///                  ExpressionParser::parse(Rule::expr, "a + bcd")?,
+///                  |a, b| b.as_str().len().cmp(&a.as_str().len()));
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"bcd\"\n"
+///         + " ├─ val \"a\"\n"
+///         + " └─ op \"+\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_sorted_by<R, F>(pairs: Pairs<R>, cmp: F) -> Result<String, TreeError>
 where
     R: pest::RuleType,
+    F: Fn(&Pair<R>, &Pair<R>) -> Ordering,
 {
-    match parsing_result {
-        Ok(pairs) => match into_ascii_tree(pairs) {
-            Ok(output) => {
-                println!("{}", output);
-            }
-            Err(e) => {
-                eprintln!("{}", e);
+    let config = TreeConfig::default();
+    Ok(render::render(
+        &tree::build_sorted(pairs, &config, &cmp),
+        &config,
+    )?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree and returns it
+/// together with the untouched `pairs`, for tests asserting on both.
+///
+/// Only a clone of the `pairs` is rendered, so the returned iterator is
+/// still at the position it was passed at. Errors while formatting the
+/// tree are rendered in place of the tree.
+///
+/// # Examples
+/// ```ignore
+/// let (tree, mut pairs) = pest_ascii_tree::tree_and_pairs(
+///                             ExpressionParser::parse(Rule::expr, "a + b")?);
+/// assert!(tree.starts_with(" expr\n"));
+/// assert_eq!(pairs.next().unwrap().as_rule(), Rule::expr);
+/// ```
+pub fn tree_and_pairs<R>(pairs: Pairs<R>) -> (String, Pairs<R>)
+where
+    R: pest::RuleType,
+{
+    let tree = into_ascii_tree(pairs.clone()).unwrap_or_else(|e| e.to_string());
+
+    (tree, pairs)
+}
+
+/// Formats the parsing result by pest into an ascii_tree with every line
+/// starting with the `prefix`, so the tree nests under existing output.
+///
+/// The first line gets the `prefix` as it is. For all following lines a
+/// connector in the `prefix` is continued instead, i.e. `├─` becomes `│ `
+/// and `└─` becomes blank, so the tree attaches to the parent's
+/// connector. Errors while formatting the tree are rendered in place of
+/// the tree.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::render_with_prefix(
+///                  ExpressionParser::parse(Rule::expr, "a + b")?, "  ├─");
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + "  ├─ expr\n"
+///         + "  │  ├─ val \"a\"\n"
+///         + "  │  ├─ op \"+\"\n"
+///         + "  │  └─ val \"b\"\n"
+/// );
+/// ```
+pub fn render_with_prefix<R>(pairs: Pairs<R>, prefix: &str) -> String
+where
+    R: pest::RuleType,
+{
+    let tree = into_ascii_tree(pairs).unwrap_or_else(|e| e.to_string());
+    let continuation: String = prefix
+        .chars()
+        .map(|c| match c {
+            '├' => '│',
+            '└' | '┌' | '─' => ' ',
+            c => c,
+        })
+        .collect();
+
+    let mut output = String::new();
+    for (i, line) in tree.lines().enumerate() {
+        output.push_str(if i == 0 { prefix } else { &continuation });
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Formats the parsing result by pest into an ascii_tree, appending the
+/// position of every node as `path:line:col` in brackets, made a
+/// clickable link to the file by OSC 8 escape sequences.
+///
+/// The link points to the canonicalized `path` if it exists, and else to
+/// the `path` relative to the current directory. Terminals not
+/// supporting OSC 8 show the position as plain text.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let input = std::fs::read_to_string("input.txt")?;
+/// print!("{}", pest_ascii_tree::into_ascii_tree_linked(
+///                  "input.txt", ExpressionParser::parse(Rule::expr, &input)?)?);
+/// ```
+pub fn into_ascii_tree_linked<R>(path: &str, pairs: Pairs<R>) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let target = file_url(path);
+
+    into_ascii_tree_annotated(pairs, |pair| {
+        let (line, col) = pair.as_span().start_pos().line_col();
+        Some(format!(
+            "\x1b]8;;{}\x1b\\{}:{}:{}\x1b]8;;\x1b\\",
+            target, path, line, col
+        ))
+    })
+}
+
+/// The `file://` URL of the `path`, canonicalized if it exists and else
+/// made absolute relative to the current directory. All bytes but
+/// unreserved ones and `/` are percent-encoded.
+fn file_url(path: &str) -> String {
+    let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.into())
+    });
+
+    let mut url = String::from("file://");
+    for byte in absolute.display().to_string().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
             }
-        },
-        Err(e) => {
-            eprintln!("{}", e);
+            _ => url.push_str(&format!("%{:02X}", byte)),
         }
     }
+    url
 }
 
-#[cfg(test)]
-mod tests {
+/// Formats the parsing result by pest into an ascii_tree, preceded by a
+/// header line naming the start `rule` and the length of the `input`.
+///
+/// This makes captured output traceable back to what was parsed.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let input = "a + b";
+/// let result = pest_ascii_tree::into_ascii_tree_with_header(
+///                  Rule::expr, input, ExpressionParser::parse(Rule::expr, input)?)?;
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + "── parse of Rule::expr (5 bytes) ──\n"
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"b\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_with_header<R>(
+    rule: R,
+    input: &str,
+    pairs: Pairs<R>,
+) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let name = render::escape_rule_name(&tree::rule_name(rule));
+    let type_name = std::any::type_name::<R>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+    let qualified = if type_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        format!("{}::{}", type_name, name)
+    } else {
+        name
+    };
 
-    use super::into_ascii_tree;
-    use pest::Parser;
+    let mut output = format!("── parse of {} ({} bytes) ──\n", qualified, input.len());
+    output.push_str(&into_ascii_tree(pairs)?);
 
-    #[derive(Parser)]
-    #[grammar = "expression.pest"]
-    struct ExpressionParser;
+    Ok(output)
+}
 
-    #[test]
-    fn it_works() {
-        let result =
-            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b + c").unwrap()).unwrap();
-        assert_eq!(
-            result,
-            String::new()
-                + " expr\n"
-                + " ├─ val \"a\"\n"
-                + " ├─ op \"+\"\n"
-                + " ├─ val \"b\"\n"
-                + " ├─ op \"+\"\n"
-                + " └─ val \"c\"\n"
-        );
+/// Formats the parsing result by pest into an ascii_tree drawn inside a
+/// box, with the `title` centered in its top edge, to separate and label
+/// several trees in one output.
+///
+/// The box is as wide as the widest line of the tree, measured by the
+/// display width with the `unicode-width` feature enabled, or as needed
+/// by the title.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_framed(
+///                  ExpressionParser::parse(Rule::expr, "a + b")?, "sum");
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + "┌─── sum ────┐\n"
+///         + "│ expr       │\n"
+///         + "│ ├─ val \"a\" │\n"
+///         + "│ ├─ op \"+\"  │\n"
+///         + "│ └─ val \"b\" │\n"
+///         + "└────────────┘\n"
+/// );
+/// ```
+pub fn into_framed<R>(pairs: Pairs<R>, title: &str) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let tree = into_ascii_tree(pairs)?;
+    let title = if title.is_empty() {
+        String::new()
+    } else {
+        format!(" {} ", title)
+    };
 
-        let result =
-            into_ascii_tree(ExpressionParser::parse(Rule::expr_root, "x + y + z").unwrap())
-                .unwrap();
-        assert_eq!(
-            result,
-            String::new()
-                + " ├─ val \"x\"\n"
-                + " ├─ op \"+\"\n"
-                + " ├─ val \"y\"\n"
-                + " ├─ op \"+\"\n"
-                + " └─ val \"z\"\n"
-        );
+    // the tree's lines start with a space, so one is added at the end.
+    let max_width = tree.lines().map(width::display_width).max().unwrap_or(0);
+    let inner = (max_width + 1).max(width::display_width(&title) + 2);
 
-        let result = into_ascii_tree(ExpressionParser::parse(Rule::val, "m").unwrap()).unwrap();
-        assert_eq!(result, String::new() + " val \"m\"\n");
+    let left = (inner - width::display_width(&title)) / 2;
+    let right = inner - width::display_width(&title) - left;
+    let mut output = format!("┌{}{}{}┐\n", "─".repeat(left), title, "─".repeat(right));
+    for line in tree.lines() {
+        let pad = inner - width::display_width(line);
+        output.push_str(&format!("│{}{}│\n", line, " ".repeat(pad)));
+    }
+    output.push_str(&format!("└{}┘\n", "─".repeat(inner)));
 
-        let result = into_ascii_tree(
-            ExpressionParser::parse(Rule::expr, "(u + (v + w)) + (x + y) + z").unwrap(),
-        )
-        .unwrap();
-        assert_eq!(
-            result,
-            String::new()
-                + " expr\n"
-                + " ├─ expr\n"
-                + " │  ├─ val \"u\"\n"
-                + " │  ├─ op \"+\"\n"
-                + " │  └─ expr\n"
-                + " │     ├─ val \"v\"\n"
-                + " │     ├─ op \"+\"\n"
-                + " │     └─ val \"w\"\n"
-                + " ├─ op \"+\"\n"
-                + " ├─ expr\n"
-                + " │  ├─ val \"x\"\n"
-                + " │  ├─ op \"+\"\n"
-                + " │  └─ val \"y\"\n"
-                + " ├─ op \"+\"\n"
-                + " └─ val \"z\"\n"
-        );
+    Ok(output)
+}
 
-        let result = into_ascii_tree(
-            ExpressionParser::parse(Rule::root, "(u + (v + w)) + (x + y) + z").unwrap(),
-        )
-        .unwrap();
-        assert_eq!(
-            result,
-            String::new()
-                + " expr\n"
-                + " ├─ expr\n"
-                + " │  ├─ val \"u\"\n"
-                + " │  ├─ op \"+\"\n"
+fn collect_leaves(nodes: &[tree::ParseTreeNode], leaves: &mut Vec<(String, String)>) {
+    for node in nodes {
+        if node.is_leaf() {
+            leaves.push((node.rule.clone(), node.content().to_string()));
+        } else {
+            collect_leaves(&node.children, leaves);
+        }
+    }
+}
+
+/// Formats the parsing result by pest into an ascii_tree, showing only
+/// the nodes whose content `predicate` returns `true` for, together with
+/// their ancestors for context.
+///
+/// The content is the matched text without surrounding whitespace. All
+/// other nodes are left out. A matching node whose children were all left
+/// out is shown as a leaf with its content.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_content_matching(
+///                  ExpressionParser::parse(Rule::expr, "a + (TODO - c)")?,
+///                  |content| content.contains("TODO"));
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " └─ expr\n"
+///         + "    └─ val \"TODO\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_content_matching<R, F>(
+    pairs: Pairs<R>,
+    predicate: F,
+) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+    F: Fn(&str) -> bool,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::retain(tree::build(pairs, &config), &|node| {
+        predicate(node.content())
+    });
+
+    Ok(render::render(&nodes, &config)?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree, showing only
+/// the nodes starting at or after the `byte_offset`, e.g. the freshly
+/// parsed region after an edit.
+///
+/// Nodes ending before the offset are left out. Nodes starting before
+/// and ending after it are kept for context, so ancestors of the shown
+/// nodes are always kept. A kept node whose children were all left out
+/// is shown as a leaf with its content.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_since(
+///                  ExpressionParser::parse(Rule::expr, "a + (b - c)")?, 7);
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " └─ expr\n"
+///         + "    ├─ op \"-\"\n"
+///         + "    └─ val \"c\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_since<R>(pairs: Pairs<R>, byte_offset: usize) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::retain(tree::build(pairs, &config), &|node| {
+        node.span.start() >= byte_offset || node.span.end() > byte_offset
+    });
+
+    Ok(render::render(&nodes, &config)?)
+}
+
+/// Whether the span of the `node` covers a character on the `line`. A span
+/// ending right after a line break doesn't cover the following line.
+fn covers_line(node: &tree::ParseTreeNode, line: usize) -> bool {
+    let start = node.span.start_pos().line_col();
+    let end = node.span.end_pos().line_col();
+    let last_line = if end.1 == 1 && end.0 > start.0 {
+        end.0 - 1
+    } else {
+        end.0
+    };
+
+    start.0 <= line && line <= last_line
+}
+
+/// Converts the path from the `node` to the innermost node covering the
+/// `line`, with the latter's children but not their descendants.
+fn path_to_line(node: &tree::ParseTreeNode, line: usize, config: &TreeConfig) -> ascii_tree::Tree {
+    let mut covering = node
+        .children
+        .iter()
+        .filter(|child| covers_line(child, line));
+    let tree = render::into_ascii_tree_node(node, config);
+
+    match (covering.next(), covering.next(), tree) {
+        (Some(child), None, ascii_tree::Tree::Node(title, _)) => {
+            ascii_tree::Tree::Node(title, vec![path_to_line(child, line, config)])
+        }
+        (_, _, ascii_tree::Tree::Node(title, children)) => {
+            let children = children
+                .into_iter()
+                .map(|child| match child {
+                    ascii_tree::Tree::Node(title, _) => ascii_tree::Tree::Node(title, Vec::new()),
+                    leaf => leaf,
+                })
+                .collect();
+            ascii_tree::Tree::Node(title, children)
+        }
+        (_, _, leaf) => leaf,
+    }
+}
+
+/// Formats the path from the root to the innermost node covering the
+/// source `line`, counting from 1, into an ascii_tree. The children of
+/// this node are shown too, but not their descendants.
+///
+/// If several siblings cover the line, their parent is the innermost
+/// node. A node ending right after a line break doesn't cover the
+/// following line. If no node covers the line, the tree is empty.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_at_line(
+///                  ExpressionParser::parse(Rule::expr, input)?, 3)?;
+/// ```
+pub fn into_ascii_tree_at_line<R>(pairs: Pairs<R>, line: usize) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+    let trees: Vec<_> = nodes
+        .iter()
+        .filter(|node| covers_line(node, line))
+        .map(|node| path_to_line(node, line, &config))
+        .collect();
+
+    Ok(render::render_trees(&trees, &config)?)
+}
+
+/// Formats only the subtree of the `n`th occurrence of the `rule` into an
+/// ascii_tree, counting from 0 in traversal order.
+///
+/// Returns `None` if the rule occurs `n` times or less, or if writing the
+/// formatted tree failed.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_nth(
+///                  ExpressionParser::parse(Rule::expr, "a + (b - c)")?, Rule::op, 1);
+/// assert_eq!(result, Some(String::from(" op \"-\"\n")));
+/// ```
+pub fn into_ascii_tree_nth<R>(pairs: Pairs<R>, rule: R, n: usize) -> Option<String>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+    let name = tree::rule_name(rule);
+    let remaining = std::cell::Cell::new(n);
+
+    let node = tree::find(&nodes, &|node| {
+        if node.rule != name {
+            return false;
+        }
+        let found = remaining.get() == 0;
+        remaining.set(remaining.get().wrapping_sub(1));
+        found
+    })?;
+
+    render::render(std::slice::from_ref(node), &config).ok()
+}
+
+/// Returns the rule name and the content of every leaf in document order,
+/// without the structure in between.
+///
+/// The content is the matched text without surrounding whitespace and
+/// isn't escaped. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let leaves = pest_ascii_tree::leaf_values(ExpressionParser::parse(Rule::expr, "a + (b)")?);
+/// assert_eq!(
+///     leaves,
+///     vec![
+///         ("val".to_string(), "a".to_string()),
+///         ("op".to_string(), "+".to_string()),
+///         ("val".to_string(), "b".to_string()),
+///     ]
+/// );
+/// ```
+pub fn leaf_values<R>(pairs: Pairs<R>) -> Vec<(String, String)>
+where
+    R: pest::RuleType,
+{
+    let mut leaves = Vec::new();
+    collect_leaves(&tree::build(pairs, &TreeConfig::default()), &mut leaves);
+
+    leaves
+}
+
+/// Returns the rule names of the top-level pairs.
+///
+/// Like in the rendered tree, the `EOI` rule is excluded.
+///
+/// # Examples
+/// ```ignore
+/// let roots = pest_ascii_tree::root_rules(ExpressionParser::parse(Rule::expr_root, "a + b")?);
+/// assert_eq!(roots, vec!["val", "op", "val"]);
+/// ```
+pub fn root_rules<R>(pairs: Pairs<R>) -> Vec<String>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    pairs
+        .map(|pair| tree::rule_name(pair.as_rule()))
+        .filter(|rule| !config.is_skipped(rule))
+        .collect()
+}
+
+/// Returns the depth of the deepest node, without rendering anything.
+///
+/// The top-level pairs are at depth 0, their inner pairs at depth 1 and
+/// so on. Empty `pairs` also result in 0. Pairs left out of the tree,
+/// like `EOI`, don't count.
+///
+/// # Examples
+/// ```ignore
+/// let depth = pest_ascii_tree::max_depth(ExpressionParser::parse(Rule::expr, "a + (b)")?);
+/// assert_eq!(depth, 2);
+/// ```
+pub fn max_depth<R>(pairs: Pairs<R>) -> usize
+where
+    R: pest::RuleType,
+{
+    tree::depth(&tree::build(pairs, &TreeConfig::default()))
+}
+
+/// Formats the parsing result by pest into an ascii_tree, also returning
+/// how long building and rendering the tree took.
+///
+/// Parsing isn't included, as the pairs are already parsed. This helps
+/// to decide whether [`write_ascii_tree_streaming`] is worth it for huge
+/// trees. Errors while formatting the tree are rendered in place of the
+/// tree.
+///
+/// # Examples
+/// ```ignore
+/// let (tree, duration) = pest_ascii_tree::render_timed(
+///                            ExpressionParser::parse(Rule::expr, input)?);
+/// eprintln!("rendered {} bytes in {:?}", tree.len(), duration);
+/// ```
+///
+/// [`write_ascii_tree_streaming`]: fn.write_ascii_tree_streaming.html
+pub fn render_timed<R>(pairs: Pairs<R>) -> (String, Duration)
+where
+    R: pest::RuleType,
+{
+    let start = Instant::now();
+    let output = into_ascii_tree(pairs).unwrap_or_else(|e| e.to_string());
+
+    (output, start.elapsed())
+}
+
+/// Formats a pest token stream into an ascii_tree stored in a [`String`].
+///
+/// The nesting of the rules is reconstructed from the matching
+/// `Token::Start` and `Token::End` tokens, so the output is the same as
+/// rendering the pairs the tokens were taken from.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let pairs = ExpressionParser::parse(Rule::expr, "a + b")?;
+/// let result = pest_ascii_tree::into_ascii_tree_from_tokens(pairs.tokens())?;
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/nightly/alloc/string/struct.String.html
+pub fn into_ascii_tree_from_tokens<R>(tokens: Tokens<R>) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    Ok(render::render(
+        &tree::build_from_tokens(tokens, &config),
+        &config,
+    )?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree, returning
+/// every line as its own [`String`].
+///
+/// The lines don't contain the trailing `\n` and there is no empty
+/// element at the end.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let lines = pest_ascii_tree::into_ascii_tree_lines(
+///                 ExpressionParser::parse(Rule::expr, "a + b")?)?;
+/// assert_eq!(lines, vec![" expr", " ├─ val \"a\"", " ├─ op \"+\"", " └─ val \"b\""]);
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/nightly/alloc/string/struct.String.html
+pub fn into_ascii_tree_lines<R>(pairs: Pairs<R>) -> Result<Vec<String>, TreeError>
+where
+    R: pest::RuleType,
+{
+    let output = into_ascii_tree(pairs)?;
+
+    Ok(output.lines().map(String::from).collect())
+}
+
+/// Formats the parsing result by pest into an ascii_tree stored in the
+/// caller's `buf`, reusing its allocation.
+///
+/// The buffer is cleared first, so no stale content of a previous call
+/// ends up in the output.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let mut buf = String::with_capacity(1024);
+/// for input in inputs {
+///     pest_ascii_tree::into_ascii_tree_into(ExpressionParser::parse(Rule::expr, input)?, &mut buf)?;
+///     println!("{}", buf);
+/// }
+/// ```
+pub fn into_ascii_tree_into<R>(pairs: Pairs<R>, buf: &mut String) -> Result<(), TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    Ok(render::render_into(
+        &tree::build(pairs, &config),
+        &config,
+        buf,
+    )?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree like
+/// [`into_ascii_tree`], without allocating for an empty tree.
+///
+/// If there are no pairs, or all of them are skipped like `EOI`,
+/// `Cow::Borrowed("")` is returned. Otherwise the rendered tree is
+/// returned as `Cow::Owned`.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_cow(ExpressionParser::parse(Rule::val, "a")?)?;
+/// assert_eq!(result, " val \"a\"\n");
+/// ```
+///
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+pub fn into_ascii_tree_cow<R>(pairs: Pairs<R>) -> Result<Cow<'static, str>, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+    if nodes.is_empty() {
+        return Ok(Cow::Borrowed(""));
+    }
+
+    Ok(Cow::Owned(render::render(&nodes, &config)?))
+}
+
+/// Formats several parsing results by pest into ascii_trees, separated
+/// by a blank line, using the same `config` for all of them.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let forest = pest_ascii_tree::render_forest(
+///     &[
+///         ExpressionParser::parse(Rule::expr, "a + b")?,
+///         ExpressionParser::parse(Rule::val, "c")?,
+///     ],
+///     &TreeConfig::default(),
+/// )?;
+/// assert_eq!(
+///     forest,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"b\"\n"
+///         + "\n"
+///         + " val \"c\"\n"
+/// );
+/// ```
+pub fn render_forest<R>(results: &[Pairs<R>], config: &TreeConfig) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let mut output = String::new();
+    let mut tree = String::new();
+
+    let mut sections: Vec<_> = results
+        .iter()
+        .map(|pairs| tree::build(pairs.clone(), config))
+        .collect();
+    tree::sort_sections(&mut sections, config.forest_order, |nodes| nodes);
+
+    for (i, nodes) in sections.iter().enumerate() {
+        if i > 0 {
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+        render::render_into(nodes, config, &mut tree)?;
+        output.push_str(&tree);
+    }
+
+    Ok(output)
+}
+
+/// Formats the parsing result by pest into an ascii_tree containing at
+/// most `max_nodes` nodes.
+///
+/// The nodes are counted in traversal order. If the tree has more nodes,
+/// the rendering stops after the `max_nodes`th node, keeping the tree's
+/// connector lines intact, and appends `… (truncated at N nodes)`.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let result = pest_ascii_tree::into_ascii_tree_max_nodes(
+///                  ExpressionParser::parse(Rule::expr, "a + b + c")?, 3)?;
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " └─ op \"+\"\n"
+///         + "… (truncated at 3 nodes)\n"
+/// );
+/// ```
+pub fn into_ascii_tree_max_nodes<R>(pairs: Pairs<R>, max_nodes: usize) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    into_ascii_tree_with_config(
+        pairs,
+        &TreeConfig {
+            max_nodes: Some(max_nodes),
+            ..Default::default()
+        },
+    )
+}
+
+/// Formats the parsing result by pest into an ascii_tree, failing if it
+/// contains a rule whose name isn't in `allowed`.
+///
+/// # Error
+/// [`TreeError::UnexpectedRule`] names the first rule in traversal order
+/// not found in `allowed`, together with its position. If writing the
+/// formatted tree failed, the error variant is passed to the caller.
+///
+/// # Examples
+/// ```ignore
+/// let allowed = ["expr", "val"].iter().map(|name| name.to_string()).collect();
+/// let result = pest_ascii_tree::into_ascii_tree_strict(
+///                  ExpressionParser::parse(Rule::expr, "a + b")?, &allowed);
+/// assert_eq!(result.unwrap_err().to_string(), "unexpected rule `op` at 1:3");
+/// ```
+///
+/// [`TreeError::UnexpectedRule`]: enum.TreeError.html#variant.UnexpectedRule
+pub fn into_ascii_tree_strict<R>(
+    pairs: Pairs<R>,
+    allowed: &HashSet<String>,
+) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::build(pairs, &config);
+
+    if let Some(node) = tree::find(&nodes, &|node| !allowed.contains(&node.rule)) {
+        let (line, col) = node.span.start_pos().line_col();
+        return Err(TreeError::UnexpectedRule {
+            rule: node.rule.clone(),
+            line,
+            col,
+        });
+    }
+
+    Ok(render::render(&nodes, &config)?)
+}
+
+/// Formats the parsing result by pest into an ascii_tree headed by a line
+/// telling whether the pairs consumed the whole `input`.
+///
+/// The header is `✓ full match` if the last top-level pair ends at the
+/// end of `input` and `✗ partial match` otherwise. The returned `bool`
+/// carries the same information for use in assertions.
+///
+/// # Examples
+/// ```ignore
+/// let input = "a + b )";
+/// let (full, output) = pest_ascii_tree::into_ascii_tree_checked(
+///                          input, ExpressionParser::parse(Rule::expr, input)?);
+/// assert!(!full);
+/// assert_eq!(
+///     output,
+///     String::new()
+///         + "✗ partial match\n"
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"b\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_checked<R>(input: &str, pairs: Pairs<R>) -> (bool, String)
+where
+    R: pest::RuleType,
+{
+    let consumed = pairs.clone().last().map_or(0, |pair| pair.as_span().end());
+    let full_match = consumed == input.len();
+
+    let mut output = String::from(if full_match {
+        "✓ full match\n"
+    } else {
+        "✗ partial match\n"
+    });
+    match into_ascii_tree(pairs) {
+        Ok(tree) => output.push_str(&tree),
+        Err(e) => output.push_str(&e.to_string()),
+    }
+
+    (full_match, output)
+}
+
+/// Formats optional pairs into an ascii_tree, rendering `None` as
+/// `(none)`.
+///
+/// Errors while formatting the tree are rendered in place of the tree.
+///
+/// # Examples
+/// ```ignore
+/// let inner = pairs.next().map(|pair| pair.into_inner());
+/// println!("{}", pest_ascii_tree::into_ascii_tree_opt(inner));
+/// ```
+pub fn into_ascii_tree_opt<R>(opt: Option<Pairs<R>>) -> String
+where
+    R: pest::RuleType,
+{
+    into_ascii_tree_opt_with_config(opt, &TreeConfig::default())
+}
+
+/// Like [`into_ascii_tree_opt`], but using the options set in the
+/// `config`. `None` is rendered as the [`TreeConfig::empty_placeholder`]
+/// if set, or as `(none)` otherwise.
+///
+/// [`into_ascii_tree_opt`]: fn.into_ascii_tree_opt.html
+/// [`TreeConfig::empty_placeholder`]: struct.TreeConfig.html#structfield.empty_placeholder
+pub fn into_ascii_tree_opt_with_config<R>(opt: Option<Pairs<R>>, config: &TreeConfig) -> String
+where
+    R: pest::RuleType,
+{
+    match opt {
+        Some(pairs) => into_ascii_tree_with_config(pairs, config).unwrap_or_else(|e| e.to_string()),
+        None => {
+            let mut output = config
+                .empty_placeholder
+                .clone()
+                .unwrap_or_else(|| String::from("(none)"));
+            if config.trailing_newline {
+                output.push('\n');
+            }
+            output
+        }
+    }
+}
+
+/// Formats the result returned by your pest Parser into an ascii_tree, or
+/// into the formatted error if parsing failed.
+///
+/// # Examples
+/// ```ignore
+/// let output = pest_ascii_tree::into_ascii_tree_result(ExpressionParser::parse(Rule::expr, input));
+/// ```
+pub fn into_ascii_tree_result<R>(result: Result<Pairs<R>, Error<R>>) -> String
+where
+    R: pest::RuleType,
+{
+    match result {
+        Ok(pairs) => into_ascii_tree_opt(Some(pairs)),
+        Err(e) => format!("{}\n", e),
+    }
+}
+
+/// Prints the result returned by your pest Parser as an ascii tree.
+///
+/// # Errors
+/// In case of an parsing error, the error is printed.
+/// In case of a formating error, the error is printed.
+///
+/// # Examples
+/// ```ignore
+/// pest_ascii_tree::print_ascii_tree(
+///                     ExpressionParser::parse(Rule::expr,
+///                                             "(u + (v + w)) + (x + y) + z"));
+/// ```
+///
+/// will result in the output
+///
+/// <pre>
+///  expr
+///  ├─ expr
+///  │  ├─ val "u"
+///  │  ├─ op "+"
+///  │  └─ expr
+///  │     ├─ val "v"
+///  │     ├─ op "+"
+///  │     └─ val "w"
+///  ├─ op "+"
+///  ├─ expr
+///  │  ├─ val "x"
+///  │  ├─ op "+"
+///  │  └─ val "y"
+///  ├─ op "+"
+///  └─ val "z"
+/// </pre>
+///
+pub fn print_ascii_tree<R>(parsing_result: Result<Pairs<R>, Error<R>>)
+where
+    R: pest::RuleType,
+{
+    match parsing_result {
+        Ok(pairs) => match into_ascii_tree(pairs) {
+            Ok(output) => {
+                println!("{}", output);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+            }
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Writes the ascii tree of the parsing result to the `writer`, using the
+/// options set in the `config`. If parsing failed, the error is written
+/// instead.
+///
+/// # Error
+/// Errors of the `writer` are passed to the caller.
+///
+/// # Examples
+/// ```ignore
+/// pest_ascii_tree::print_ascii_tree_to(
+///     &mut std::io::stdout(),
+///     ExpressionParser::parse(Rule::expr, "a + b"),
+///     &TreeConfig::default())?;
+/// ```
+pub fn print_ascii_tree_to<W, R>(
+    writer: &mut W,
+    parsing_result: Result<Pairs<R>, Error<R>>,
+    config: &TreeConfig,
+) -> std::io::Result<()>
+where
+    W: std::io::Write,
+    R: pest::RuleType,
+{
+    match parsing_result {
+        Ok(pairs) => match into_ascii_tree_with_config(pairs, config) {
+            Ok(output) => writeln!(writer, "{}", output),
+            Err(e) => writeln!(writer, "{}", e),
+        },
+        Err(e) => writeln!(writer, "{}", e),
+    }
+}
+
+/// Formats the parsing result by pest into an ascii_tree, converting both
+/// parsing and formatting errors into an [`anyhow::Error`] with context.
+///
+/// # Error
+/// If parsing failed, the pest error is returned with the context
+/// `parsing failed`. If writing the formatted tree failed, the error is
+/// returned with the context `failed to format the ascii tree`.
+///
+/// # Examples
+/// ```ignore
+/// fn main() -> anyhow::Result<()> {
+///     let input = std::fs::read_to_string("input.txt")?;
+///     println!("{}", pest_ascii_tree::into_ascii_tree_anyhow(
+///                        ExpressionParser::parse(Rule::expr, &input))?);
+///     Ok(())
+/// }
+/// ```
+///
+/// [`anyhow::Error`]: https://docs.rs/anyhow/1/anyhow/struct.Error.html
+#[cfg(feature = "anyhow")]
+pub fn into_ascii_tree_anyhow<R>(
+    parsing_result: Result<Pairs<R>, Error<R>>,
+) -> anyhow::Result<String>
+where
+    R: pest::RuleType + Send + Sync + 'static,
+{
+    use anyhow::Context;
+
+    let pairs = parsing_result.context("parsing failed")?;
+    into_ascii_tree(pairs).context("failed to format the ascii tree")
+}
+
+/// Renders the tree on success, or the error followed by the `partial`
+/// tree labeled as incomplete on failure.
+fn with_partial<R>(
+    parsing_result: Result<Pairs<R>, Error<R>>,
+    partial: Option<Pairs<R>>,
+) -> Result<String, String>
+where
+    R: pest::RuleType,
+{
+    match parsing_result {
+        Ok(pairs) => into_ascii_tree(pairs).map_err(|e| e.to_string()),
+        Err(e) => {
+            let mut output = e.to_string();
+            if let Some(partial) = partial {
+                output.push_str("\npartial tree (incomplete):\n");
+                match into_ascii_tree(partial) {
+                    Ok(tree) => output.push_str(&tree),
+                    Err(e) => output.push_str(&e.to_string()),
+                }
+            }
+            Err(output)
+        }
+    }
+}
+
+/// Like [`print_ascii_tree`], but if parsing failed, the `partial` pairs
+/// captured before the failure are printed after the error, labeled as
+/// `partial tree (incomplete)`.
+///
+/// This shows how far parsing got before failing.
+///
+/// # Examples
+/// ```ignore
+/// let partial = ExpressionParser::parse(Rule::expr, "a +").ok();
+/// pest_ascii_tree::print_ascii_tree_with_partial(
+///     ExpressionParser::parse(Rule::root, "a +"),
+///     partial);
+/// ```
+///
+/// [`print_ascii_tree`]: fn.print_ascii_tree.html
+pub fn print_ascii_tree_with_partial<R>(
+    parsing_result: Result<Pairs<R>, Error<R>>,
+    partial: Option<Pairs<R>>,
+) where
+    R: pest::RuleType,
+{
+    match with_partial(parsing_result, partial) {
+        Ok(output) => {
+            println!("{}", output);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Reads the file at `path`, parses it with the `rule` and renders the
+/// tree. Every error message includes the path.
+fn render_file<P, R>(rule: R, path: &Path) -> Result<String, String>
+where
+    P: pest::Parser<R>,
+    R: pest::RuleType,
+{
+    let input = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let pairs =
+        P::parse(rule, &input).map_err(|e| e.with_path(&path.to_string_lossy()).to_string())?;
+
+    into_ascii_tree(pairs).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Reads the file at `path`, parses it with the parser `P` starting at
+/// the `rule` and prints the ascii tree to stdout.
+///
+/// If reading or parsing the file failed, the error including the path is
+/// printed to stderr instead.
+///
+/// # Examples
+/// ```ignore
+/// pest_ascii_tree::print_ascii_tree_from_file::<ExpressionParser, _>(
+///     Rule::expr,
+///     Path::new("input.txt"));
+/// ```
+pub fn print_ascii_tree_from_file<P, R>(rule: R, path: &Path)
+where
+    P: pest::Parser<R>,
+    R: pest::RuleType,
+{
+    match render_file::<P, R>(rule, path) {
+        Ok(output) => {
+            println!("{}", output);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Describes the result returned by your pest Parser in a single
+/// [`String`].
+///
+/// On success the report consists of a header with the length of the
+/// `input` followed by the ascii tree. On failure it contains the error
+/// as formatted by pest, including the affected part of the input.
+///
+/// # Examples
+/// ```ignore
+/// let input = "a + b";
+/// let report = pest_ascii_tree::debug_report(input, ExpressionParser::parse(Rule::expr, input));
+/// assert_eq!(
+///     report,
+///     String::new()
+///         + "parsing succeeded (5 bytes of input)\n"
+///         + " expr\n"
+///         + " ├─ val \"a\"\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ val \"b\"\n"
+/// );
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/nightly/alloc/string/struct.String.html
+pub fn debug_report<R>(input: &str, result: Result<Pairs<R>, Error<R>>) -> String
+where
+    R: pest::RuleType,
+{
+    match result {
+        Ok(pairs) => {
+            let mut report = format!("parsing succeeded ({} bytes of input)\n", input.len());
+            match into_ascii_tree(pairs) {
+                Ok(tree) => report.push_str(&tree),
+                Err(e) => report.push_str(&format!("{}\n", e)),
+            }
+            report
+        }
+        Err(e) => format!("parsing failed ({} bytes of input)\n{}\n", input.len(), e),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+    use pest::Parser;
+
+    #[derive(Parser)]
+    #[grammar = "expression.pest"]
+    pub(crate) struct ExpressionParser;
+
+    #[test]
+    fn it_works() {
+        let result =
+            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b + c").unwrap()).unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ val \"b\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"c\"\n"
+        );
+
+        let result =
+            into_ascii_tree(ExpressionParser::parse(Rule::expr_root, "x + y + z").unwrap())
+                .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ├─ val \"x\"\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ val \"y\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"z\"\n"
+        );
+
+        let result = into_ascii_tree(ExpressionParser::parse(Rule::val, "m").unwrap()).unwrap();
+        assert_eq!(result, String::new() + " val \"m\"\n");
+
+        let result = into_ascii_tree(
+            ExpressionParser::parse(Rule::expr, "(u + (v + w)) + (x + y) + z").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"u\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ expr\n"
+                + " │     ├─ val \"v\"\n"
+                + " │     ├─ op \"+\"\n"
+                + " │     └─ val \"w\"\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"x\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"y\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"z\"\n"
+        );
+
+        let result = into_ascii_tree(
+            ExpressionParser::parse(Rule::root, "(u + (v + w)) + (x + y) + z").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"u\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ expr\n"
+                + " │     ├─ val \"v\"\n"
+                + " │     ├─ op \"+\"\n"
+                + " │     └─ val \"w\"\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"x\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"y\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"z\"\n"
+        );
+
+        let result = into_ascii_tree(
+            ExpressionParser::parse(Rule::expr_root, "(u + (v + w)) + (x + y) + z").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ├─ expr\n"
+                + " │  ├─ val \"u\"\n"
+                + " │  ├─ op \"+\"\n"
                 + " │  └─ expr\n"
                 + " │     ├─ val \"v\"\n"
                 + " │     ├─ op \"+\"\n"
                 + " │     └─ val \"w\"\n"
                 + " ├─ op \"+\"\n"
                 + " ├─ expr\n"
-                + " │  ├─ val \"x\"\n"
-                + " │  ├─ op \"+\"\n"
-                + " │  └─ val \"y\"\n"
-                + " ├─ op \"+\"\n"
-                + " └─ val \"z\"\n"
+                + " │  ├─ val \"x\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"y\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"z\"\n"
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let lines =
+            into_ascii_tree_lines(ExpressionParser::parse(Rule::expr, "a + b").unwrap()).unwrap();
+        assert_eq!(
+            lines,
+            vec![" expr", " ├─ val \"a\"", " ├─ op \"+\"", " └─ val \"b\""]
+        );
+    }
+
+    #[test]
+    fn collapse_unary_chains() {
+        let config = TreeConfig {
+            collapse_unary_chains: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "((a))").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, " expr>expr>expr>val \"a\"\n");
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "(a) + ((b + c))").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr>val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr>expr\n"
+                + "    ├─ val \"b\"\n"
+                + "    ├─ op \"+\"\n"
+                + "    └─ val \"c\"\n"
+        );
+    }
+
+    #[test]
+    fn checked() {
+        let input = "a + b";
+        let (full_match, result) =
+            into_ascii_tree_checked(input, ExpressionParser::parse(Rule::root, input).unwrap());
+        assert!(full_match);
+        assert_eq!(
+            result,
+            String::new()
+                + "✓ full match\n"
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+        );
+
+        let input = "a + b )";
+        let (full_match, result) =
+            into_ascii_tree_checked(input, ExpressionParser::parse(Rule::expr, input).unwrap());
+        assert!(!full_match);
+        assert!(result.starts_with("✗ partial match\n expr\n"));
+    }
+
+    #[test]
+    fn truncate_content() {
+        let config = TreeConfig {
+            truncate_content: Some(4),
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "abc + abcdef").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"abc\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"abc…\"\n"
+        );
+    }
+
+    #[test]
+    fn reuse_buffer() {
+        let mut buf = String::from("stale content\n");
+
+        into_ascii_tree_into(ExpressionParser::parse(Rule::val, "m").unwrap(), &mut buf).unwrap();
+        assert_eq!(buf, " val \"m\"\n");
+
+        into_ascii_tree_into(
+            ExpressionParser::parse(Rule::expr_root, "x + y").unwrap(),
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(
+            buf,
+            String::new() + " ├─ val \"x\"\n" + " ├─ op \"+\"\n" + " └─ val \"y\"\n"
+        );
+    }
+
+    #[test]
+    fn report() {
+        let input = "a + b";
+        let report = debug_report(input, ExpressionParser::parse(Rule::expr, input));
+        assert_eq!(
+            report,
+            String::new()
+                + "parsing succeeded (5 bytes of input)\n"
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+        );
+
+        let input = "a + ";
+        let report = debug_report(input, ExpressionParser::parse(Rule::root, input));
+        assert!(report.starts_with("parsing failed (4 bytes of input)\n"));
+        assert!(report.contains("1:5"));
+    }
+
+    #[test]
+    fn max_nodes() {
+        let result =
+            into_ascii_tree_max_nodes(ExpressionParser::parse(Rule::expr, "a + b + c").unwrap(), 3)
+                .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " └─ op \"+\"\n"
+                + "… (truncated at 3 nodes)\n"
+        );
+
+        let result = into_ascii_tree_max_nodes(
+            ExpressionParser::parse(Rule::expr, "(u + (v + w)) + x").unwrap(),
+            6,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " └─ expr\n"
+                + "    ├─ val \"u\"\n"
+                + "    ├─ op \"+\"\n"
+                + "    └─ expr\n"
+                + "       └─ val \"v\"\n"
+                + "… (truncated at 6 nodes)\n"
+        );
+
+        let result =
+            into_ascii_tree_max_nodes(ExpressionParser::parse(Rule::expr, "a + b").unwrap(), 4)
+                .unwrap();
+        assert!(!result.contains("truncated"));
+    }
+
+    #[test]
+    fn skip_rules() {
+        let config = TreeConfig {
+            skip_rules: vec![String::from("OP")],
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " expr\n" + " ├─ val \"a\"\n" + " ├─ op \"+\"\n" + " └─ val \"b\"\n"
+        );
+
+        let config = TreeConfig {
+            case_insensitive_rules: true,
+            ..config
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " expr\n" + " ├─ val \"a\"\n" + " └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn root_separator() {
+        let config = TreeConfig {
+            root_separator: Some(String::from("────")),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr_root, "(a + b) + c").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ├─ expr\n"
+                + " │  ├─ val \"a\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"b\"\n"
+                + "────\n"
+                + " ├─ op \"+\"\n"
+                + "────\n"
+                + " └─ val \"c\"\n"
+        );
+
+        let result =
+            into_ascii_tree_with_config(ExpressionParser::parse(Rule::val, "m").unwrap(), &config)
+                .unwrap();
+        assert_eq!(result, " val \"m\"\n");
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let config = TreeConfig {
+            trailing_newline: false,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " expr\n" + " ├─ val \"a\"\n" + " ├─ op \"+\"\n" + " └─ val \"b\""
+        );
+    }
+
+    #[test]
+    fn from_tokens() {
+        let pairs = ExpressionParser::parse(Rule::root, "(u + (v + w)) + z").unwrap();
+        assert_eq!(
+            into_ascii_tree_from_tokens(pairs.clone().tokens()).unwrap(),
+            into_ascii_tree(pairs).unwrap()
+        );
+
+        let pairs = ExpressionParser::parse(Rule::expr_root, "x + y").unwrap();
+        assert_eq!(pairs.clone().tokens().count(), 6);
+        assert_eq!(
+            into_ascii_tree_from_tokens(pairs.tokens()).unwrap(),
+            String::new() + " ├─ val \"x\"\n" + " ├─ op \"+\"\n" + " └─ val \"y\"\n"
+        );
+    }
+
+    #[test]
+    fn name_transform() {
+        assert_eq!(NameCase::Title.apply("func_def"), "Func Def");
+        assert_eq!(NameCase::Title.apply("expr>val"), "Expr>Val");
+        assert_eq!(NameCase::Upper.apply("func_def"), "FUNC_DEF");
+        assert_eq!(NameCase::Lower.apply("EOI"), "eoi");
+
+        let config = TreeConfig {
+            name_transform: NameCase::Title,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " Expr\n" + " ├─ Val \"a\"\n" + " ├─ Op \"+\"\n" + " └─ Val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn fold_literal_leaves() {
+        let config = TreeConfig {
+            fold_literal_leaves: vec![String::from("+")].into_iter().collect(),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b - c + d").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr [+ +]\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ val \"b\"\n"
+                + " ├─ op \"-\"\n"
+                + " ├─ val \"c\"\n"
+                + " └─ val \"d\"\n"
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let nodes = convert_pairs(ExpressionParser::parse(Rule::expr_root, "a + (b)").unwrap());
+        assert_eq!(nodes.len(), 3);
+
+        let mut output = String::new();
+        ascii_tree::write_tree(&mut output, &ascii_tree::Tree::Node("input".into(), nodes))
+            .unwrap();
+        assert_eq!(
+            output,
+            String::new()
+                + " input\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr\n"
+                + "    └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn hide_empty_leaves() {
+        let result =
+            into_ascii_tree(ExpressionParser::parse(Rule::statement, "a + b").unwrap()).unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " statement\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"a\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"b\"\n"
+                + " └─ terminator \"\"\n"
+        );
+
+        let config = TreeConfig {
+            hide_empty_leaves: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::statement, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " statement\n"
+                + " └─ expr\n"
+                + "    ├─ val \"a\"\n"
+                + "    ├─ op \"+\"\n"
+                + "    └─ val \"b\"\n"
+        );
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::statement, "a + b;").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert!(result.ends_with(" └─ terminator \";\"\n"));
+    }
+
+    #[test]
+    fn forest() {
+        let results = [
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            ExpressionParser::parse(Rule::expr_root, "c + d").unwrap(),
+        ];
+
+        let forest = render_forest(&results, &TreeConfig::default()).unwrap();
+        assert_eq!(
+            forest,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+                + "\n"
+                + " ├─ val \"c\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"d\"\n"
+        );
+
+        let config = TreeConfig {
+            trailing_newline: false,
+            name_transform: NameCase::Upper,
+            ..Default::default()
+        };
+        let forest = render_forest(&results, &config).unwrap();
+        assert!(forest.contains(" └─ VAL \"b\"\n\n ├─ VAL \"c\""));
+        assert!(forest.ends_with(" └─ VAL \"d\""));
+    }
+
+    #[test]
+    fn leaf_path_prefix() {
+        let config = TreeConfig {
+            leaf_path_prefix: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::statement, "(a) + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " statement\n"
+                + " ├─ expr\n"
+                + " │  ├─ expr\n"
+                + " │  │  └─ statement.expr.expr.val \"a\"\n"
+                + " │  ├─ statement.expr.op \"+\"\n"
+                + " │  └─ statement.expr.val \"b\"\n"
+                + " └─ statement.terminator \"\"\n"
+        );
+
+        let result =
+            into_ascii_tree_with_config(ExpressionParser::parse(Rule::val, "m").unwrap(), &config)
+                .unwrap();
+        assert_eq!(result, " val \"m\"\n");
+    }
+
+    #[test]
+    fn strict() {
+        let mut allowed: HashSet<String> = ["expr", "val"].iter().map(|s| s.to_string()).collect();
+
+        let result = into_ascii_tree_strict(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &allowed,
+        );
+        match result {
+            Err(TreeError::UnexpectedRule { rule, line, col }) => {
+                assert_eq!((rule.as_str(), line, col), ("op", 1, 3));
+            }
+            _ => panic!("expected an unexpected rule error"),
+        }
+
+        allowed.insert(String::from("op"));
+        let result = into_ascii_tree_strict(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &allowed,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b").unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn depth_gutter() {
+        let config = TreeConfig {
+            show_depth_gutter: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + "0│ expr\n"
+                + "1│ ├─ val \"a\"\n"
+                + "1│ ├─ op \"+\"\n"
+                + "1│ └─ expr\n"
+                + "2│    └─ val \"b\"\n"
+        );
+
+        let input = "((((((((((a))))))))))";
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, input).unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert!(result.starts_with(" 0│ expr\n 1│ └─ expr\n"));
+        assert!(result.ends_with(&format!("11│{} └─ val \"a\"\n", " ".repeat(30))));
+    }
+
+    #[test]
+    fn opt_and_result() {
+        assert_eq!(into_ascii_tree_opt::<Rule>(None), "(none)\n");
+        assert_eq!(
+            into_ascii_tree_opt(Some(ExpressionParser::parse(Rule::val, "m").unwrap())),
+            " val \"m\"\n"
+        );
+
+        let config = TreeConfig {
+            empty_placeholder: Some(String::from("<nothing>")),
+            ..Default::default()
+        };
+        assert_eq!(
+            into_ascii_tree_opt_with_config::<Rule>(None, &config),
+            "<nothing>\n"
+        );
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::terminator, "").unwrap(),
+            &TreeConfig {
+                hide_empty_leaves: true,
+                ..config
+            },
+        )
+        .unwrap();
+        assert_eq!(result, "<nothing>\n");
+
+        assert_eq!(
+            into_ascii_tree_result(ExpressionParser::parse(Rule::val, "m")),
+            " val \"m\"\n"
+        );
+        assert!(into_ascii_tree_result(ExpressionParser::parse(Rule::val, "+")).contains("1:1"));
+    }
+
+    #[test]
+    fn deterministic() {
+        let input = "(u + (v + w)) + (x + y) - z";
+        let config = TreeConfig {
+            fold_literal_leaves: vec!["+", "-"].into_iter().map(String::from).collect(),
+            skip_rules: vec![String::from("terminator")],
+            ..Default::default()
+        };
+        let render = || {
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr, input).unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+
+        let expected = render();
+        for _ in 0..100 {
+            assert_eq!(render().as_bytes(), expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn multi_root_style() {
+        let render = |multi_root| {
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr_root, "(a) + b").unwrap(),
+                &TreeConfig {
+                    multi_root,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            render(MultiRootStyle::NumberedRoots),
+            String::new()
+                + " [root 0]\n"
+                + " └─ expr\n"
+                + "    └─ val \"a\"\n"
+                + " [root 1]\n"
+                + " └─ op \"+\"\n"
+                + " [root 2]\n"
+                + " └─ val \"b\"\n"
+        );
+        assert_eq!(
+            render(MultiRootStyle::NamedRoot(String::from("input"))),
+            String::new()
+                + " input\n"
+                + " ├─ expr\n"
+                + " │  └─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+        );
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::val, "m").unwrap(),
+            &TreeConfig {
+                multi_root: MultiRootStyle::NumberedRoots,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(result, " val \"m\"\n");
+    }
+
+    #[test]
+    fn inverted() {
+        let result =
+            into_ascii_tree_inverted(ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap())
+                .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ┌─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " │  ┌─ val \"b\"\n"
+                + " │  ├─ op \"-\"\n"
+                + " │  ├─ val \"c\"\n"
+                + " ├─ expr\n"
+                + " expr\n"
+        );
+
+        let result =
+            into_ascii_tree_inverted(ExpressionParser::parse(Rule::expr_root, "x + y").unwrap())
+                .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " ┌─ val \"x\"\n" + " ├─ op \"+\"\n" + " ├─ val \"y\"\n"
+        );
+    }
+
+    #[test]
+    fn custom_escaper() {
+        let result = into_ascii_tree_with_escaper(
+            ExpressionParser::parse(Rule::expr, "a + 42").unwrap(),
+            |content| content.replace(|c: char| c.is_ascii_digit(), "#"),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " expr\n" + " ├─ val \"a\"\n" + " ├─ op \"+\"\n" + " └─ val \"##\"\n"
+        );
+    }
+
+    #[test]
+    fn mark_emptied_nodes() {
+        let config = TreeConfig {
+            skip_rules: vec![String::from("val"), String::from("op")],
+            mark_emptied_nodes: true,
+            ..Default::default()
+        };
+        let pairs = ExpressionParser::parse(Rule::expr, "a + (b)").unwrap();
+        let expected = String::new() + " expr\n" + " └─ expr\n" + "    └─ (empty)\n";
+        assert_eq!(
+            into_ascii_tree_with_config(pairs.clone(), &config).unwrap(),
+            expected
+        );
+        assert_eq!(
+            render::render(
+                &tree::build_from_tokens(pairs.clone().tokens(), &config),
+                &config
+            )
+            .unwrap(),
+            expected
+        );
+
+        let config = TreeConfig {
+            mark_emptied_nodes: false,
+            ..config
+        };
+        assert_eq!(
+            into_ascii_tree_with_config(pairs, &config).unwrap(),
+            String::new() + " expr\n" + " └─ expr \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn max_line_width() {
+        let config = TreeConfig {
+            max_line_width: Some(40),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(
+                Rule::expr,
+                "a + ((((((((((x + abcdefghijklmnopqrstuvwxyz))))))))))",
+            )
+            .unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert!(result.lines().all(|line| line.chars().count() <= 40));
+        assert_eq!(
+            result.lines().collect::<Vec<_>>()[12..],
+            [
+                "                            └─ expr",
+                "…                            ├─ val \"x\"",
+                "                               ├─ op \"+\"",
+                "…                            └─ val \"…\"",
+            ]
+        );
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + abcdefghijklmnopqrstuvwxyzabcdefghij")
+                .unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result.lines().last(),
+            Some(" └─ val \"abcdefghijklmnopqrstuvwxyzabc…\"")
+        );
+
+        let config = TreeConfig {
+            max_line_width: Some(40),
+            root_separator: Some("─".repeat(50)),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr_root, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        let separator = "─".repeat(50);
+        assert_eq!(
+            result,
+            String::new()
+                + " ├─ val \"a\"\n"
+                + &separator
+                + "\n ├─ op \"+\"\n"
+                + &separator
+                + "\n └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn render_timed() {
+        let (result, duration) =
+            super::render_timed(ExpressionParser::parse(Rule::expr, "a + b").unwrap());
+        assert_eq!(
+            result,
+            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + b").unwrap()).unwrap()
+        );
+        assert!(duration < Duration::from_secs(60));
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    enum WeirdRule {
+        Weird,
+    }
+
+    impl std::fmt::Debug for WeirdRule {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("two\nlines \"quoted\"\u{7}")
+        }
+    }
+
+    #[test]
+    fn escape_rule_names() {
+        let parse = || {
+            pest::state("x", |state| {
+                state.rule(WeirdRule::Weird, |state| state.match_string("x"))
+            })
+            .unwrap()
+        };
+        let expected = " two\\nlines \\\"quoted\\\"\\u{7} \"x\"\n";
+        assert_eq!(into_ascii_tree(parse()).unwrap(), expected);
+
+        let mut streamed = String::new();
+        write_ascii_tree_streaming(&mut streamed, parse()).unwrap();
+        assert_eq!(streamed, expected);
+
+        let config = TreeConfig {
+            name_transform: NameCase::Upper,
+            ..Default::default()
+        };
+        assert_eq!(
+            into_ascii_tree_with_config(parse(), &config).unwrap(),
+            " TWO\\nLINES \\\"QUOTED\\\"\\u{7} \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn header() {
+        let input = "a + b";
+        let result = into_ascii_tree_with_header(
+            Rule::expr,
+            input,
+            ExpressionParser::parse(Rule::expr, input).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + "── parse of Rule::expr (5 bytes) ──\n"
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn max_depth() {
+        let depth = |input| super::max_depth(ExpressionParser::parse(Rule::expr, input).unwrap());
+        assert_eq!(depth("a"), 1);
+        assert_eq!(depth("a + (b)"), 2);
+        assert_eq!(depth("((((a))))"), 5);
+        assert_eq!(
+            super::max_depth(ExpressionParser::parse(Rule::root, "a").unwrap()),
+            1
+        );
+    }
+
+    #[cfg(feature = "pest_vm")]
+    #[test]
+    fn max_depth_without_eoi() {
+        let grammar = "file = { SOI ~ ASCII_DIGIT* ~ EOI }";
+        let (_, rules) = pest_meta::parse_and_optimize(grammar).unwrap();
+        let vm = pest_vm::Vm::new(rules);
+
+        assert_eq!(super::max_depth(vm.parse("file", "12").unwrap()), 0);
+    }
+
+    #[test]
+    fn content_on_new_line() {
+        let config = TreeConfig {
+            content_on_new_line: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val\n"
+                + " │  \"a\"\n"
+                + " ├─ op\n"
+                + " │  \"+\"\n"
+                + " └─ expr\n"
+                + "    └─ val\n"
+                + "       \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn render_file() {
+        let dir = std::env::temp_dir().join(format!("pest_ascii_tree-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let valid = dir.join("valid.txt");
+        std::fs::write(&valid, "a + b").unwrap();
+        assert_eq!(
+            super::render_file::<ExpressionParser, _>(Rule::expr, &valid),
+            Ok(String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"b\"\n")
+        );
+
+        let invalid = dir.join("invalid.txt");
+        std::fs::write(&invalid, "+").unwrap();
+        let e = super::render_file::<ExpressionParser, _>(Rule::expr, &invalid).unwrap_err();
+        assert!(e.contains("invalid.txt"), "{}", e);
+
+        let missing = dir.join("missing.txt");
+        let e = super::render_file::<ExpressionParser, _>(Rule::expr, &missing).unwrap_err();
+        assert!(e.starts_with(&missing.display().to_string()), "{}", e);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dedup_subtrees() {
+        let config = TreeConfig {
+            dedup_subtrees: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "(a + b) - (a + b) - (a - b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"a\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"b\"\n"
+                + " ├─ op \"-\"\n"
+                + " ├─ expr ↑ same as line 2\n"
+                + " ├─ op \"-\"\n"
+                + " └─ expr\n"
+                + "    ├─ val \"a\"\n"
+                + "    ├─ op \"-\"\n"
+                + "    └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn with_partial() {
+        let partial = ExpressionParser::parse(Rule::expr, "a +").ok();
+        let e =
+            super::with_partial(ExpressionParser::parse(Rule::root, "a +"), partial).unwrap_err();
+        assert!(e.starts_with(
+            &ExpressionParser::parse(Rule::root, "a +")
+                .unwrap_err()
+                .to_string()
+        ));
+        assert!(
+            e.ends_with("\npartial tree (incomplete):\n expr\n └─ val \"a\"\n"),
+            "{}",
+            e
+        );
+
+        let e = super::with_partial(ExpressionParser::parse(Rule::root, "a +"), None).unwrap_err();
+        assert!(!e.contains("partial tree"));
+
+        assert_eq!(
+            super::with_partial(ExpressionParser::parse(Rule::root, "a"), None),
+            Ok(String::new() + " expr\n" + " └─ val \"a\"\n")
+        );
+    }
+
+    #[test]
+    fn indent_width() {
+        let render = |indent_width| {
+            let config = TreeConfig {
+                indent_width,
+                ..Default::default()
+            };
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            render(3),
+            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap()).unwrap()
+        );
+        assert_eq!(
+            render(2),
+            String::new()
+                + " expr\n"
+                + "├─ val \"a\"\n"
+                + "├─ op \"+\"\n"
+                + "└─ expr\n"
+                + "  └─ val \"b\"\n"
+        );
+        assert_eq!(
+            render(5),
+            String::new()
+                + " expr\n"
+                + " ├─── val \"a\"\n"
+                + " ├─── op \"+\"\n"
+                + " └─── expr\n"
+                + "      └─── val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn annotated() {
+        let result = into_ascii_tree_annotated(
+            ExpressionParser::parse(Rule::expr, "a + 42").unwrap(),
+            |pair| match pair.as_rule() {
+                Rule::expr => Some(format!("{} bytes", pair.as_str().len())),
+                _ => pair
+                    .as_str()
+                    .parse::<i64>()
+                    .ok()
+                    .map(|n| format!("{:#x}", n)),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr [6 bytes]\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"42\" [0x2a]\n"
+        );
+    }
+
+    #[test]
+    fn render_with_prefix() {
+        let pairs = || ExpressionParser::parse(Rule::expr, "a + b").unwrap();
+        assert_eq!(
+            super::render_with_prefix(pairs(), "  ├─"),
+            String::new()
+                + "  ├─ expr\n"
+                + "  │  ├─ val \"a\"\n"
+                + "  │  ├─ op \"+\"\n"
+                + "  │  └─ val \"b\"\n"
+        );
+        assert_eq!(
+            super::render_with_prefix(pairs(), "└─"),
+            String::new()
+                + "└─ expr\n"
+                + "   ├─ val \"a\"\n"
+                + "   ├─ op \"+\"\n"
+                + "   └─ val \"b\"\n"
+        );
+        assert_eq!(
+            super::render_with_prefix(pairs(), "> "),
+            String::new()
+                + ">  expr\n"
+                + ">  ├─ val \"a\"\n"
+                + ">  ├─ op \"+\"\n"
+                + ">  └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn tree_and_pairs() {
+        let mut pairs = ExpressionParser::parse(Rule::expr_root, "a + b").unwrap();
+        pairs.next();
+
+        let (tree, mut pairs) = super::tree_and_pairs(pairs);
+        assert_eq!(tree, String::new() + " ├─ op \"+\"\n" + " └─ val \"b\"\n");
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::op);
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::val);
+        assert!(pairs.next().is_none());
+    }
+
+    #[test]
+    fn multiline_summary() {
+        let config = TreeConfig {
+            multiline_summary: true,
+            ..Default::default()
+        };
+        let render = |input| {
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::block, input).unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+        assert_eq!(render("{a\nb\nc}"), " block \"<3 lines>\"\n");
+        assert_eq!(render("{a b}"), " block \"{a\\ b}\"\n");
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    enum QualifiedRule {
+        Value,
+    }
+
+    impl std::fmt::Debug for QualifiedRule {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("Rule::value::Rule::x")
+        }
+    }
+
+    #[test]
+    fn strip_rule_prefix() {
+        let parse = || {
+            pest::state("x", |state| {
+                state.rule(QualifiedRule::Value, |state| state.match_string("x"))
+            })
+            .unwrap()
+        };
+        let config = TreeConfig {
+            strip_rule_prefix: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            into_ascii_tree_with_config(parse(), &config).unwrap(),
+            " value::Rule::x \"x\"\n"
+        );
+
+        let config = TreeConfig {
+            rule_prefix: String::from("Rule::value::"),
+            ..config
+        };
+        assert_eq!(
+            into_ascii_tree_with_config(parse(), &config).unwrap(),
+            " Rule::x \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn print_ascii_tree_to() {
+        let mut output = Vec::new();
+        super::print_ascii_tree_to(
+            &mut output,
+            ExpressionParser::parse(Rule::expr, "a"),
+            &TreeConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            " expr\n └─ val \"a\"\n\n"
+        );
+
+        let mut output = Vec::new();
+        super::print_ascii_tree_to(
+            &mut output,
+            ExpressionParser::parse(Rule::expr, "+"),
+            &TreeConfig::default(),
+        )
+        .unwrap();
+        let error = ExpressionParser::parse(Rule::expr, "+").unwrap_err();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", error));
+    }
+
+    #[test]
+    fn leaf_values() {
+        let leaves = super::leaf_values(ExpressionParser::parse(Rule::root, "a + (b)").unwrap());
+        let leaves: Vec<_> = leaves
+            .iter()
+            .map(|(rule, content)| (rule.as_str(), content.as_str()))
+            .collect();
+        assert_eq!(leaves, [("val", "a"), ("op", "+"), ("val", "b")]);
+    }
+
+    #[test]
+    fn node_content() {
+        let render = |node_content| {
+            let config = TreeConfig {
+                node_content,
+                ..Default::default()
+            };
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+        let children = String::new() + " ├─ val \"a\"\n" + " ├─ op \"+\"\n" + " └─ val \"b\"\n";
+        assert_eq!(
+            render(NodeContentMode::Hidden),
+            String::from(" expr\n") + &children
+        );
+        assert_eq!(
+            render(NodeContentMode::Length),
+            String::from(" expr (5 bytes)\n") + &children
+        );
+        assert_eq!(
+            render(NodeContentMode::Summary(4)),
+            String::from(" expr \"a\\ …\"\n") + &children
+        );
+        assert_eq!(
+            render(NodeContentMode::Full),
+            String::from(" expr \"a\\ +\\ b\"\n") + &children
+        );
+    }
+
+    #[test]
+    fn wrap_content() {
+        let config = TreeConfig {
+            wrap_content: Some(4),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "(abcdefghij + b) + c").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr\n"
+                + " │  ├─ val \"abcd\n"
+                + " │  │       efgh\n"
+                + " │  │       ij\"\n"
+                + " │  ├─ op \"+\"\n"
+                + " │  └─ val \"b\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"c\"\n"
+        );
+    }
+
+    #[test]
+    fn show_descendant_count() {
+        let config = TreeConfig {
+            show_descendant_count: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr (6)\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr (3)\n"
+                + "    ├─ val \"b\"\n"
+                + "    ├─ op \"-\"\n"
+                + "    └─ val \"c\"\n"
+        );
+    }
+
+    #[test]
+    fn fold_beyond_depth() {
+        let config = TreeConfig {
+            fold_beyond_depth: Some(1),
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "((5)) + (a + b) + c").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr [> expr > val \"5\"]\n"
+                + " ├─ op \"+\"\n"
+                + " ├─ expr […]\n"
+                + " ├─ op \"+\"\n"
+                + " └─ val \"c\"\n"
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_nth() {
+        let nth = |rule, n| {
+            super::into_ascii_tree_nth(
+                ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+                rule,
+                n,
+            )
+        };
+        assert_eq!(nth(Rule::op, 1), Some(String::from(" op \"-\"\n")));
+        assert_eq!(
+            nth(Rule::expr, 1),
+            Some(
+                String::new()
+                    + " expr\n"
+                    + " ├─ val \"b\"\n"
+                    + " ├─ op \"-\"\n"
+                    + " └─ val \"c\"\n"
+            )
+        );
+        assert_eq!(nth(Rule::op, 2), None);
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn into_ascii_tree_anyhow() {
+        assert_eq!(
+            super::into_ascii_tree_anyhow(ExpressionParser::parse(Rule::expr, "a")).unwrap(),
+            String::new() + " expr\n" + " └─ val \"a\"\n"
+        );
+
+        let e =
+            super::into_ascii_tree_anyhow(ExpressionParser::parse(Rule::expr, "+")).unwrap_err();
+        assert_eq!(e.to_string(), "parsing failed");
+        assert!(e.downcast_ref::<Error<Rule>>().is_some());
+    }
+
+    #[test]
+    fn show_node_ids() {
+        let config = TreeConfig {
+            show_node_ids: true,
+            ..Default::default()
+        };
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " [0] expr\n"
+                + " ├─ [0.0] val \"a\"\n"
+                + " ├─ [0.1] op \"+\"\n"
+                + " └─ [0.2] expr\n"
+                + "    └─ [0.2.0] val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn show_hex() {
+        let config = TreeConfig {
+            show_hex: true,
+            ..Default::default()
+        };
+        let render = |rule, input| {
+            into_ascii_tree_with_config(ExpressionParser::parse(rule, input).unwrap(), &config)
+                .unwrap()
+        };
+        assert_eq!(
+            render(Rule::expr, "a + 1"),
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\" (0x61)\n"
+                + " ├─ op \"+\" (0x2B)\n"
+                + " └─ val \"1\" (0x31)\n"
+        );
+        assert_eq!(
+            render(Rule::block, "{\tü}"),
+            " block \"{\\tü}\" (0x7B 0x09 0xC3 0xBC 0x7D)\n"
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_linked() {
+        let result = super::into_ascii_tree_linked(
+            "/nonexistent/input.txt",
+            ExpressionParser::parse(Rule::expr, "a").unwrap(),
+        )
+        .unwrap();
+        let link = |line, col| {
+            format!(
+                "\x1b]8;;file:///nonexistent/input.txt\x1b\\/nonexistent/input.txt:{}:{}\x1b]8;;\x1b\\",
+                line, col
+            )
+        };
+        assert_eq!(
+            result,
+            format!(" expr [{}]\n └─ val \"a\" [{}]\n", link(1, 1), link(1, 1))
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_linked_relative() {
+        let result = super::into_ascii_tree_linked(
+            "nonexistent/input.txt",
+            ExpressionParser::parse(Rule::val, "a").unwrap(),
+        )
+        .unwrap();
+        let target = std::env::current_dir()
+            .unwrap()
+            .join("nonexistent/input.txt");
+        assert_eq!(
+            result,
+            format!(
+                " val \"a\" [\x1b]8;;file://{}\x1b\\nonexistent/input.txt:1:1\x1b]8;;\x1b\\]\n",
+                target.display()
+            )
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_linked_encoded() {
+        let result = super::into_ascii_tree_linked(
+            "/nonexistent dir/a#b%.txt",
+            ExpressionParser::parse(Rule::val, "a").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            " val \"a\" [\x1b]8;;file:///nonexistent%20dir/a%23b%25.txt\x1b\\/nonexistent dir/a#b%.txt:1:1\x1b]8;;\x1b\\]\n"
+        );
+    }
+
+    #[test]
+    fn forest_order() {
+        let results = [
+            ExpressionParser::parse(Rule::val, "c").unwrap(),
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            ExpressionParser::parse(Rule::op, "+").unwrap(),
+        ];
+        let first_lines = |forest_order| {
+            let config = TreeConfig {
+                forest_order,
+                ..Default::default()
+            };
+            render_forest(&results, &config)
+                .unwrap()
+                .split("\n\n")
+                .map(|section| section.lines().next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            first_lines(ForestOrder::Input),
+            [" val \"c\"", " expr", " op \"+\""]
+        );
+        assert_eq!(
+            first_lines(ForestOrder::NodeCount),
+            [" expr", " val \"c\"", " op \"+\""]
+        );
+        assert_eq!(
+            first_lines(ForestOrder::RuleName),
+            [" expr", " op \"+\"", " val \"c\""]
+        );
+
+        let config = TreeConfig {
+            forest_order: ForestOrder::RuleName,
+            ..Default::default()
+        };
+        assert_eq!(
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr_root, "a + b").unwrap(),
+                &config
+            )
+            .unwrap(),
+            String::new() + " ├─ op \"+\"\n" + " ├─ val \"a\"\n" + " └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn root_rules() {
+        assert_eq!(
+            super::root_rules(ExpressionParser::parse(Rule::expr_root, "a + b").unwrap()),
+            ["val", "op", "val"]
+        );
+        assert_eq!(
+            super::root_rules(ExpressionParser::parse(Rule::root, "a").unwrap()),
+            ["expr"]
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        let config = TreeConfig {
+            canonical: true,
+            ..Default::default()
+        };
+        let render = |input| {
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::block, input).unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+        assert_eq!(render("{a \n\t  b}"), " block \"{a\\ b}\"\n");
+        assert_eq!(render("{a \n\t  b}"), render("{a b}"));
+    }
+
+    #[test]
+    fn content_matching() {
+        let result = into_ascii_tree_content_matching(
+            ExpressionParser::parse(Rule::expr, "a + (TODO - c) + TODO").unwrap(),
+            |content| content.contains("TODO"),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ expr\n"
+                + " │  └─ val \"TODO\"\n"
+                + " └─ val \"TODO\"\n"
         );
 
-        let result = into_ascii_tree(
-            ExpressionParser::parse(Rule::expr_root, "(u + (v + w)) + (x + y) + z").unwrap(),
+        let result = into_ascii_tree_content_matching(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            |content| content == "+",
+        )
+        .unwrap();
+        assert_eq!(result, String::new() + " expr\n" + " └─ op \"+\"\n");
+    }
+
+    #[test]
+    fn into_ascii_tree_at_line() {
+        let input = "{a}{b}\n{c\nd}\n";
+        let at_line = |line| {
+            super::into_ascii_tree_at_line(
+                ExpressionParser::parse(Rule::lines, input).unwrap(),
+                line,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            at_line(1),
+            String::new()
+                + " lines\n"
+                + " ├─ block \"{a}\"\n"
+                + " ├─ block \"{b}\"\n"
+                + " └─ block \"{c\\nd}\"\n"
+        );
+        assert_eq!(
+            at_line(3),
+            String::new() + " lines\n" + " └─ block \"{c\\nd}\"\n"
+        );
+        assert_eq!(at_line(3), at_line(2));
+        assert_eq!(at_line(4), "");
+    }
+
+    #[test]
+    fn truncate_at_separator() {
+        let mut config = TreeConfig {
+            truncate_content: Some(12),
+            truncate_at: TruncateAt::Separator(','),
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::block, "{1, 22, 333}").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, " block \"{1,\\ 22,…\"\n");
+
+        config.truncate_at = TruncateAt::Separator(';');
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::block, "{1, 22, 333}").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, " block \"{1,\\ 22,\\ 3…\"\n");
+    }
+
+    #[test]
+    fn merge_same_rule_leaves() {
+        let mut config = TreeConfig {
+            merge_same_rule_leaves: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::lines, "{a}{b}\n{c}\n").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " lines\n" + " └─ block \"{a},\\ {b},\\ {c}\"\n"
+        );
+
+        config.merge_separator = String::from("|");
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b) - c").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert!(result.contains(" ├─ op \"+\"\n ├─ expr\n"));
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::lines, "{a}{b}\n").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " lines\n" + " └─ block \"{a}|{b}\"\n"
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_sorted_by() {
+        let result = super::into_ascii_tree_sorted_by(
+            ExpressionParser::parse(Rule::expr, "a + (bcd - e)").unwrap(),
+            |a, b| b.as_str().len().cmp(&a.as_str().len()),
         )
         .unwrap();
         assert_eq!(
             result,
             String::new()
+                + " expr\n"
                 + " ├─ expr\n"
-                + " │  ├─ val \"u\"\n"
-                + " │  ├─ op \"+\"\n"
-                + " │  └─ expr\n"
-                + " │     ├─ val \"v\"\n"
-                + " │     ├─ op \"+\"\n"
-                + " │     └─ val \"w\"\n"
+                + " │  ├─ val \"bcd\"\n"
+                + " │  ├─ op \"-\"\n"
+                + " │  └─ val \"e\"\n"
+                + " ├─ val \"a\"\n"
+                + " └─ op \"+\"\n"
+        );
+    }
+
+    #[test]
+    fn skip_last_top_level() {
+        let config = TreeConfig {
+            skip_last_top_level: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::root, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, "");
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr_root, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, String::new() + " ├─ val \"a\"\n" + " └─ op \"+\"\n");
+    }
+
+    #[test]
+    fn truncate_head_tail() {
+        let config = TreeConfig {
+            truncate_content: Some(6),
+            truncate_at: TruncateAt::HeadTail { head: 3, tail: 2 },
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::lines, "{abcdef}{ab}\n").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " lines\n" + " ├─ block \"{ab…f}\"\n" + " └─ block \"{ab}\"\n"
+        );
+    }
+
+    #[test]
+    fn show_child_count() {
+        let config = TreeConfig {
+            show_child_count: true,
+            show_descendant_count: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr {3} (4)\n"
+                + " ├─ val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr {1} (1)\n"
+                + "    └─ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn rule_glyphs() {
+        let mut config = TreeConfig::default();
+        config
+            .rule_glyphs
+            .insert("val".to_string(), "#".to_string());
+        config
+            .rule_glyphs
+            .insert("expr".to_string(), "▶".to_string());
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ▶ expr\n"
+                + " ├─ # val \"a\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ # val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn hide_all_content() {
+        let config = TreeConfig {
+            hide_all_content: true,
+            ..Default::default()
+        };
+
+        let render = |input| {
+            into_ascii_tree_with_config(
+                ExpressionParser::parse(Rule::expr, input).unwrap(),
+                &config,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            render("a + (b)"),
+            String::new() + " expr\n" + " ├─ val\n" + " ├─ op\n" + " └─ expr\n" + "    └─ val\n"
+        );
+        assert_eq!(render("a + (b)"), render("12 - (xyz)"));
+    }
+
+    #[test]
+    fn inline_leaf_siblings() {
+        let config = TreeConfig {
+            inline_leaf_siblings: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b - c) + (d)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"a\"\n"
                 + " ├─ op \"+\"\n"
                 + " ├─ expr\n"
-                + " │  ├─ val \"x\"\n"
-                + " │  ├─ op \"+\"\n"
-                + " │  └─ val \"y\"\n"
+                + " │  └─ [val \"b\", op \"-\", val \"c\"]\n"
                 + " ├─ op \"+\"\n"
-                + " └─ val \"z\"\n"
+                + " └─ expr\n"
+                + "    └─ val \"d\"\n"
+        );
+    }
+
+    #[test]
+    fn interpret_numbers() {
+        use crate::fixtures::json::{self, JsonParser};
+
+        let config = TreeConfig {
+            interpret_numbers: true,
+            ..Default::default()
+        };
+
+        let result = fixtures::render::<JsonParser, _>(
+            json::Rule::json,
+            "[1, 0xff, -2.50, true, \"x1\"]",
+            &config,
+        );
+        assert_eq!(
+            result,
+            String::new()
+                + " array\n"
+                + " ├─ number \"1\" (1)\n"
+                + " ├─ number \"0xff\" (255)\n"
+                + " ├─ number \"-2.50\" (-2.5)\n"
+                + " ├─ boolean \"true\"\n"
+                + " └─ string\n"
+                + "    └─ text \"x1\"\n"
+        );
+    }
+
+    #[test]
+    fn global_align() {
+        use crate::fixtures::json::{self, JsonParser};
+
+        let config = TreeConfig {
+            global_align: true,
+            ..Default::default()
+        };
+
+        let result = fixtures::render::<JsonParser, _>(
+            json::Rule::json,
+            "{\"a\": 1, \"bc\": [true]}",
+            &config,
+        );
+        assert_eq!(
+            result,
+            String::new()
+                + " object\n"
+                + " ├─ pair\n"
+                + " │  ├─ string\n"
+                + " │  │  └─ text    \"a\"\n"
+                + " │  └─ number     \"1\"\n"
+                + " └─ pair\n"
+                + "    ├─ string\n"
+                + "    │  └─ text    \"bc\"\n"
+                + "    └─ array\n"
+                + "       └─ boolean \"true\"\n"
+        );
+    }
+
+    #[test]
+    fn rtl() {
+        let config = TreeConfig {
+            rtl: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + "         expr\n"
+                + "   \"a\" val ─┤\n"
+                + "    \"+\" op ─┤\n"
+                + "      expr ─┘\n"
+                + "\"b\" val ─┘\n"
+        );
+    }
+
+    #[test]
+    fn highlight_longest_leaf() {
+        let config = TreeConfig {
+            highlight_longest_leaf: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "ab + (abc - xyz)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " expr\n"
+                + " ├─ val \"ab\"\n"
+                + " ├─ op \"+\"\n"
+                + " └─ expr\n"
+                + "    ├─ » val \"abc\"\n"
+                + "    ├─ op \"-\"\n"
+                + "    └─ val \"xyz\"\n"
+        );
+    }
+
+    #[test]
+    fn depth_gradient() {
+        let config = TreeConfig {
+            depth_gradient: true,
+            ..Default::default()
+        };
+
+        let result = into_ascii_tree_with_config(
+            ExpressionParser::parse(Rule::expr, "a + (b)").unwrap(),
+            &config,
+        )
+        .unwrap();
+        if render::no_color() {
+            assert_eq!(
+                result,
+                into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + (b)").unwrap()).unwrap()
+            );
+        } else {
+            assert_eq!(
+                result,
+                String::new()
+                    + "\x1b[48;5;240m expr\x1b[0m\n"
+                    + "\x1b[48;5;239m ├─ val \"a\"\x1b[0m\n"
+                    + "\x1b[48;5;239m ├─ op \"+\"\x1b[0m\n"
+                    + "\x1b[48;5;239m └─ expr\x1b[0m\n"
+                    + "\x1b[48;5;238m    └─ val \"b\"\x1b[0m\n"
+            );
+        }
+    }
+
+    #[test]
+    fn collapse_same_rule_runs() {
+        use crate::fixtures::list::{self, ListParser};
+
+        let config = TreeConfig {
+            collapse_same_rule_runs: true,
+            ..Default::default()
+        };
+
+        let result = fixtures::render::<ListParser, _>(list::Rule::list, "(1234, ab, 5)", &config);
+        assert_eq!(
+            result,
+            String::new()
+                + " list\n"
+                + " ├─ number\n"
+                + " │  └─ digit ×4 [\"1\",\"2\",\"3\",\"4\"]\n"
+                + " ├─ word \"ab\"\n"
+                + " └─ number\n"
+                + "    └─ digit \"5\"\n"
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_cow() {
+        let result =
+            super::into_ascii_tree_cow(ExpressionParser::parse(Rule::expr, "a").unwrap()).unwrap();
+        assert!(matches!(result, Cow::Owned(_)));
+        assert_eq!(result, " expr\n └─ val \"a\"\n");
+
+        let mut pairs = ExpressionParser::parse(Rule::expr, "a").unwrap();
+        pairs.next();
+        let result = super::into_ascii_tree_cow(pairs).unwrap();
+        assert!(matches!(result, Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn into_ascii_tree_with_status() {
+        let result = super::into_ascii_tree_with_status(
+            ExpressionParser::parse(Rule::expr, "a + (12 - b)").unwrap(),
+            |pair| match pair.as_str() {
+                "12" => Status::Error,
+                "-" => Status::Warn,
+                _ => Status::Ok,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + " ✅ expr\n"
+                + " ├─ ✅ val \"a\"\n"
+                + " ├─ ✅ op \"+\"\n"
+                + " └─ ✅ expr\n"
+                + "    ├─ ❌ val \"12\"\n"
+                + "    ├─ ⚠️ op \"-\"\n"
+                + "    └─ ✅ val \"b\"\n"
+        );
+    }
+
+    #[test]
+    fn into_ascii_tree_since() {
+        let since = |offset| {
+            super::into_ascii_tree_since(
+                ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+                offset,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            since(7),
+            String::new() + " expr\n" + " └─ expr\n" + "    ├─ op \"-\"\n" + "    └─ val \"c\"\n"
+        );
+        assert_eq!(
+            since(0),
+            into_ascii_tree(ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap()).unwrap()
+        );
+        assert_eq!(since(11), "");
+    }
+
+    #[test]
+    fn into_framed() {
+        let result =
+            super::into_framed(ExpressionParser::parse(Rule::expr, "a + b").unwrap(), "sum")
+                .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + "┌─── sum ────┐\n"
+                + "│ expr       │\n"
+                + "│ ├─ val \"a\" │\n"
+                + "│ ├─ op \"+\"  │\n"
+                + "│ └─ val \"b\" │\n"
+                + "└────────────┘\n"
+        );
+
+        let result = super::into_framed(
+            ExpressionParser::parse(Rule::val, "a").unwrap(),
+            "a longer title",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new()
+                + "┌─ a longer title ─┐\n"
+                + "│ val \"a\"          │\n"
+                + "└──────────────────┘\n"
+        );
+    }
+
+    #[test]
+    fn abbreviate_rules() {
+        use crate::fixtures::json::{self, JsonParser};
+
+        let config = TreeConfig {
+            abbreviate_rules: Some(3),
+            ..Default::default()
+        };
+
+        let result = fixtures::render::<JsonParser, _>(json::Rule::json, "[1, null]", &config);
+        assert_eq!(
+            result,
+            String::new()
+                + " ar.\n"
+                + " ├─ nu. \"1\"\n"
+                + " └─ nu. \"null\"\n"
+                + "abbreviations:\n"
+                + "  nu.: null, number\n"
+        );
+
+        let result = fixtures::render::<JsonParser, _>(json::Rule::json, "[true]", &config);
+        assert_eq!(result, String::new() + " ar.\n" + " └─ bo. \"true\"\n");
+    }
+
+    #[test]
+    fn content_field_width() {
+        use crate::fixtures::json::{self, JsonParser};
+
+        let config = TreeConfig {
+            content_field_width: Some(6),
+            interpret_numbers: true,
+            global_align: true,
+            ..Default::default()
+        };
+
+        let result =
+            fixtures::render::<JsonParser, _>(json::Rule::json, "[123456789, 1, [null]]", &config);
+        assert_eq!(
+            result,
+            String::new()
+                + " array\n"
+                + " ├─ number  \"123…\" (123456789)\n"
+                + " ├─ number  \"1\"    (1)\n"
+                + " └─ array\n"
+                + "    └─ null \"null\"\n"
         );
     }
 }
@@ -0,0 +1,74 @@
+//! Filtering the parse tree by a regular expression on the rule names.
+
+use crate::config::TreeConfig;
+use crate::error::TreeError;
+use crate::render;
+use crate::tree;
+use pest::iterators::Pairs;
+use regex::Regex;
+
+/// Formats the parsing result by pest into an ascii_tree, showing only
+/// the nodes whose rule name matches the `pattern`, together with their
+/// ancestors for context.
+///
+/// All other nodes are left out. A matching node whose children were all
+/// left out is shown as a leaf with its content.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let pattern = regex::Regex::new("^op$").unwrap();
+/// let result = pest_ascii_tree::into_ascii_tree_matching(
+///                  ExpressionParser::parse(Rule::expr, "a + (b - c)")?, &pattern)?;
+/// assert_eq!(
+///     result,
+///     String::new()
+///         + " expr\n"
+///         + " ├─ op \"+\"\n"
+///         + " └─ expr\n"
+///         + "    └─ op \"-\"\n"
+/// );
+/// ```
+pub fn into_ascii_tree_matching<R>(pairs: Pairs<R>, pattern: &Regex) -> Result<String, TreeError>
+where
+    R: pest::RuleType,
+{
+    let config = TreeConfig::default();
+    let nodes = tree::retain(tree::build(pairs, &config), &|node| {
+        pattern.is_match(&node.rule)
+    });
+
+    Ok(render::render(&nodes, &config)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn matching() {
+        let pattern = Regex::new("^op$").unwrap();
+        let result = into_ascii_tree_matching(
+            ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap(),
+            &pattern,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            String::new() + " expr\n" + " ├─ op \"+\"\n" + " └─ expr\n" + "    └─ op \"-\"\n"
+        );
+
+        let pattern = Regex::new("^nothing$").unwrap();
+        let result = into_ascii_tree_matching(
+            ExpressionParser::parse(Rule::expr, "a + b").unwrap(),
+            &pattern,
+        )
+        .unwrap();
+        assert_eq!(result, "");
+    }
+}
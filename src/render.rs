@@ -0,0 +1,1014 @@
+use crate::config::{MultiRootStyle, NodeContentMode, TreeConfig, TruncateAt};
+use crate::tree::{self, ParseTreeNode};
+use crate::width;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Write};
+
+/// Escapes leaf content the default way, like `a\ b\n`.
+pub(crate) fn default_escape(text: &str) -> String {
+    escape_string::escape(text).into_owned()
+}
+
+/// Escapes quotes, backslashes and control characters in a rule name, so
+/// it always stays on a single line and can't be mistaken for content.
+pub(crate) fn escape_rule_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_control() || c == '"' || c == '\\' {
+            escaped.extend(c.escape_default());
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    escaped
+}
+
+/// Called with every leaf, the labels of its ancestors and its ids by
+/// [`Renderer::visit_leaves`].
+type LeafVisitor<'v> = dyn FnMut(&ParseTreeNode, &[String], &[usize]) + 'v;
+
+/// Cuts the rule `name` to `max_width` columns, ending it with `.`, if
+/// it's wider.
+fn abbreviate(name: &str, max_width: usize) -> Cow<'_, str> {
+    if width::display_width(name) <= max_width {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(width::truncate_marked(name, max_width, '.'))
+    }
+}
+
+/// Renders the parse tree according to the config.
+pub(crate) struct Renderer<'a> {
+    pub config: &'a TreeConfig,
+    /// Applied to the content of every leaf, before truncating it.
+    pub escape: &'a dyn Fn(&str) -> String,
+    /// Puts the root at the bottom and the leaves at the top.
+    pub inverted: bool,
+    /// The column the contents of leaves are aligned to, measured from
+    /// the start of the roots' lines.
+    pub align_column: Option<usize>,
+    /// The ids of the leaf marked by
+    /// [`TreeConfig::highlight_longest_leaf`].
+    pub highlighted: Option<&'a [usize]>,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(config: &'a TreeConfig) -> Self {
+        Renderer {
+            config,
+            escape: &default_escape,
+            inverted: false,
+            align_column: None,
+            highlighted: None,
+        }
+    }
+
+    /// The content of the node, joined with the contents of the leaves
+    /// merged into it.
+    fn leaf_text<'n>(&self, node: &'n ParseTreeNode) -> Cow<'n, str> {
+        if node.merged.is_empty() {
+            return Cow::Borrowed(node.content());
+        }
+
+        let mut text = node.content().to_string();
+        for content in &node.merged {
+            text.push_str(&self.config.merge_separator);
+            text.push_str(content);
+        }
+        Cow::Owned(text)
+    }
+
+    fn leaf_content(&self, node: &ParseTreeNode) -> String {
+        let text = &*self.leaf_text(node);
+        if self.config.multiline_summary && text.contains('\n') {
+            return format!("<{} lines>", text.lines().count());
+        }
+        let content = if self.config.canonical {
+            let words: Vec<_> = text.split_whitespace().collect();
+            (self.escape)(&words.join(" "))
+        } else {
+            (self.escape)(text)
+        };
+
+        match (self.config.truncate_content, self.config.truncate_at) {
+            (Some(max_width), TruncateAt::Hard) => width::truncate(&content, max_width),
+            (Some(max_width), TruncateAt::Separator(separator)) => {
+                width::truncate_after(&content, max_width, separator)
+            }
+            (Some(max_width), TruncateAt::HeadTail { head, tail }) => {
+                if width::display_width(&content) > max_width {
+                    width::truncate_middle(&content, head, tail)
+                } else {
+                    content
+                }
+            }
+            (None, _) => content,
+        }
+    }
+
+    /// The rule name of the node without the
+    /// [`TreeConfig::rule_prefix`] if it's stripped.
+    fn rule_name<'n>(&self, node: &'n ParseTreeNode) -> &'n str {
+        let name = node.rule.as_str();
+        if self.config.strip_rule_prefix {
+            name.strip_prefix(self.config.rule_prefix.as_str())
+                .unwrap_or(name)
+        } else {
+            name
+        }
+    }
+
+    fn rule_label(&self, node: &ParseTreeNode) -> String {
+        let name = self.rule_name(node);
+        let shown = match self.config.abbreviate_rules {
+            Some(max_width) => abbreviate(name, max_width),
+            None => Cow::Borrowed(name),
+        };
+
+        let label = escape_rule_name(&self.config.name_transform.apply(&shown));
+        match self.config.rule_glyphs.get(name) {
+            Some(glyph) => format!("{} {}", glyph, label),
+            None => label,
+        }
+    }
+
+    /// Describes the descendants of the `node` inline, like
+    /// `> val > num "5"`, if they form a single path. Otherwise they are
+    /// elided to `…`.
+    fn folded_tail(&self, node: &ParseTreeNode) -> String {
+        let mut parts = Vec::new();
+        let mut current = node;
+        while let [child] = &current.children[..] {
+            current = child;
+            if current.is_leaf() {
+                parts.push(format!(
+                    "{} \"{}\"",
+                    self.rule_label(current),
+                    self.leaf_content(current)
+                ));
+            } else {
+                parts.push(self.rule_label(current));
+            }
+        }
+
+        if current.is_leaf() {
+            format!("> {}", parts.join(" > "))
+        } else {
+            String::from("…")
+        }
+    }
+
+    /// Converts the `index`th top-level node.
+    pub fn tree_node(&self, node: &ParseTreeNode, index: usize) -> ascii_tree::Tree {
+        self.convert(node, &mut Vec::new(), &mut vec![index])
+    }
+
+    /// The id of the node, like `[0.2.1] `, if they are shown, preceded
+    /// by the marker of the highlighted leaf.
+    fn node_id(&self, ids: &[usize]) -> String {
+        let marker = if self.highlighted == Some(ids) {
+            "» "
+        } else {
+            ""
+        };
+        if self.config.show_node_ids {
+            let ids: Vec<_> = ids.iter().map(|id| id.to_string()).collect();
+            format!("{}[{}] ", marker, ids.join("."))
+        } else {
+            marker.to_string()
+        }
+    }
+
+    /// The text in front of the label of the node: its status followed
+    /// by its id.
+    fn line_head(&self, node: &ParseTreeNode, ids: &[usize]) -> String {
+        match node.status {
+            Some(status) => format!("{} {}", status.glyph(), self.node_id(ids)),
+            None => self.node_id(ids),
+        }
+    }
+
+    /// The label of the leaf, prefixed by the labels in `path` if
+    /// [`TreeConfig::leaf_path_prefix`] is set.
+    fn leaf_label(&self, node: &ParseTreeNode, path: &[String]) -> String {
+        let label = self.rule_label(node);
+        if self.config.leaf_path_prefix && !path.is_empty() {
+            format!("{}.{}", path.join("."), label)
+        } else {
+            label
+        }
+    }
+
+    /// The number of nodes at the start of the `siblings` collapsed into
+    /// one line by [`TreeConfig::collapse_same_rule_runs`], at least 1.
+    fn run_len(&self, siblings: &[ParseTreeNode]) -> usize {
+        match siblings.first() {
+            Some(first) if self.config.collapse_same_rule_runs => siblings
+                .iter()
+                .take_while(|node| node.rule == first.rule)
+                .count(),
+            _ => 1,
+        }
+    }
+
+    /// Calls `visit` for every leaf below the node rendered with its own
+    /// line, in traversal order, following the same path as
+    /// [`Renderer::convert`].
+    fn visit_leaves(
+        &self,
+        node: &ParseTreeNode,
+        path: &mut Vec<String>,
+        ids: &mut Vec<usize>,
+        visit: &mut LeafVisitor,
+    ) {
+        let config = self.config;
+        if node.is_leaf() {
+            visit(node, path, ids);
+        } else if !matches!(config.fold_beyond_depth, Some(depth) if path.len() >= depth) {
+            path.push(self.rule_label(node));
+            let mut i = 0;
+            while let Some(child) = node.children.get(i) {
+                let run = self.run_len(&node.children[i..]);
+                let folded =
+                    child.is_leaf() && config.fold_literal_leaves.contains(child.content());
+                if run == 1 && !folded {
+                    ids.push(i);
+                    self.visit_leaves(child, path, ids, visit);
+                    ids.pop();
+                }
+                i += run;
+            }
+            path.pop();
+        }
+    }
+
+    /// Calls `visit` for every leaf of the `nodes`, like
+    /// [`Renderer::visit_leaves`].
+    fn visit_all_leaves(&self, nodes: &[ParseTreeNode], visit: &mut LeafVisitor) {
+        for (i, node) in nodes.iter().enumerate() {
+            self.visit_leaves(node, &mut Vec::new(), &mut vec![i], visit);
+        }
+    }
+
+    /// Converts the node, with `path` holding the labels of its ancestors
+    /// and `ids` the indices of the node and its ancestors among their
+    /// siblings.
+    fn convert(
+        &self,
+        node: &ParseTreeNode,
+        path: &mut Vec<String>,
+        ids: &mut Vec<usize>,
+    ) -> ascii_tree::Tree {
+        let config = self.config;
+        let mut label = self.rule_label(node);
+        let id = self.line_head(node, ids);
+
+        if node.is_leaf() {
+            label = self.leaf_label(node, path);
+            if let Some(column) = self.align_column {
+                let indent = path.len() * config.indent_width.max(1);
+                let used = indent + width::display_width(&id) + width::display_width(&label);
+                label.push_str(&" ".repeat(column.saturating_sub(used)));
+            }
+            if config.hide_all_content {
+                let mut line = format!("{}{}", id, label);
+                if let Some(annotation) = &node.annotation {
+                    line = format!("{} [{}]", line, annotation);
+                }
+                return ascii_tree::Tree::Leaf(vec![line]);
+            }
+            let mut content = self.leaf_content(node);
+            if let Some(field_width) = config.content_field_width {
+                // the quotes take two of the columns.
+                content = width::truncate(&content, field_width.saturating_sub(2).max(1));
+            }
+            let chunks = match config.wrap_content {
+                Some(max_width) => width::wrap(&content, max_width),
+                None => vec![content],
+            };
+
+            // continuation lines of the content start in its column.
+            let mut lines = Vec::new();
+            let (first, indent) = if config.content_on_new_line {
+                lines.push(format!("{}{}", id, label));
+                (String::from("\""), String::from(" "))
+            } else {
+                let indent =
+                    " ".repeat(width::display_width(&id) + width::display_width(&label) + 2);
+                (format!("{}{} \"", id, label), indent)
+            };
+            for (i, chunk) in chunks.iter().enumerate() {
+                let mut line = if i == 0 {
+                    format!("{}{}", first, chunk)
+                } else {
+                    format!("{}{}", indent, chunk)
+                };
+                if i + 1 == chunks.len() {
+                    line.push('"');
+                    let field_end = line.len();
+                    let text = self.leaf_text(node);
+                    if config.show_hex && !text.is_empty() {
+                        let hex: Vec<_> =
+                            text.bytes().map(|byte| format!("0x{:02X}", byte)).collect();
+                        line = format!("{} ({})", line, hex.join(" "));
+                    }
+                    if config.interpret_numbers {
+                        if let Some(value) = parse_number(&text) {
+                            line = format!("{} ({})", line, value);
+                        }
+                    }
+                    if let Some(annotation) = &node.annotation {
+                        line = format!("{} [{}]", line, annotation);
+                    }
+                    if let Some(field_width) = config.content_field_width {
+                        if line.len() > field_end {
+                            let used = width::display_width(chunk) + 2;
+                            line.insert_str(
+                                field_end,
+                                &" ".repeat(field_width.saturating_sub(used)),
+                            );
+                        }
+                    }
+                }
+                lines.push(line);
+            }
+            ascii_tree::Tree::Leaf(lines)
+        } else if matches!(config.fold_beyond_depth, Some(depth) if path.len() >= depth) {
+            ascii_tree::Tree::Leaf(vec![format!(
+                "{}{} [{}]",
+                id,
+                label,
+                self.folded_tail(node)
+            )])
+        } else {
+            path.push(label.clone());
+            let mut folded = Vec::new();
+            let mut children = Vec::new();
+            let mut i = 0;
+            while let Some(child) = node.children.get(i) {
+                let run = self.run_len(&node.children[i..]);
+                if run > 1 {
+                    let contents: Vec<_> = node.children[i..i + run]
+                        .iter()
+                        .map(|node| format!("\"{}\"", self.leaf_content(node)))
+                        .collect();
+                    children.push(ascii_tree::Tree::Leaf(vec![format!(
+                        "{}{} ×{} [{}]",
+                        self.line_head(child, &[&ids[..], &[i]].concat()),
+                        self.rule_label(child),
+                        run,
+                        contents.join(",")
+                    )]));
+                } else if child.is_leaf() && config.fold_literal_leaves.contains(child.content()) {
+                    folded.push(child.content());
+                } else {
+                    ids.push(i);
+                    children.push(self.convert(child, path, ids));
+                    ids.pop();
+                }
+                i += run;
+            }
+            path.pop();
+
+            if config.inline_leaf_siblings
+                && children.len() >= 2
+                && node.children.iter().all(ParseTreeNode::is_leaf)
+            {
+                let leaves: Vec<_> = children
+                    .iter()
+                    .map(|child| match child {
+                        ascii_tree::Tree::Leaf(lines) => lines.join(" "),
+                        ascii_tree::Tree::Node(title, _) => title.clone(),
+                    })
+                    .collect();
+                children = vec![ascii_tree::Tree::Leaf(vec![format!(
+                    "[{}]",
+                    leaves.join(", ")
+                )])];
+            }
+            if config.show_child_count {
+                label = format!("{} {{{}}}", label, node.children.len());
+            }
+            if config.show_descendant_count {
+                label = format!("{} ({})", label, descendant_count(node));
+            }
+            match config.node_content {
+                NodeContentMode::Hidden => {}
+                NodeContentMode::Length => {
+                    label = format!("{} ({} bytes)", label, node.content().len());
+                }
+                NodeContentMode::Summary(max_width) => {
+                    let content = width::truncate(&(self.escape)(node.content()), max_width);
+                    label = format!("{} \"{}\"", label, content);
+                }
+                NodeContentMode::Full => {
+                    label = format!("{} \"{}\"", label, self.leaf_content(node));
+                }
+            }
+            if !folded.is_empty() {
+                label = format!("{} [{}]", label, folded.join(" "));
+            }
+            if let Some(annotation) = &node.annotation {
+                label = format!("{} [{}]", label, annotation);
+            }
+            if node.emptied {
+                children.push(ascii_tree::Tree::Leaf(vec!["(empty)".to_string()]));
+            }
+            ascii_tree::Tree::Node(format!("{}{}", id, label), children)
+        }
+    }
+}
+
+/// Parses the `text` as an integer, optionally prefixed by `0x`, `0o` or
+/// `0b`, or as a float and formats its value. Returns `None` for other
+/// text, including `inf` and `NaN`.
+fn parse_number(text: &str) -> Option<String> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+    if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+
+    let radix = match digits.get(..2) {
+        Some("0x") | Some("0X") => Some(16),
+        Some("0o") | Some("0O") => Some(8),
+        Some("0b") | Some("0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        return u128::from_str_radix(&digits[2..], radix)
+            .ok()
+            .map(|value| format!("{}{}", sign, value));
+    }
+    if let Ok(value) = digits.parse::<u128>() {
+        return Some(format!("{}{}", sign, value));
+    }
+    digits
+        .parse::<f64>()
+        .ok()
+        .map(|value| format!("{}{}", sign, value))
+}
+
+/// The number of nodes below the `node`.
+fn descendant_count(node: &ParseTreeNode) -> usize {
+    node.children
+        .iter()
+        .map(|child| 1 + descendant_count(child))
+        .sum()
+}
+
+pub(crate) fn into_ascii_tree_node(node: &ParseTreeNode, config: &TreeConfig) -> ascii_tree::Tree {
+    Renderer::new(config).tree_node(node, 0)
+}
+
+/// Drops all nodes following the first `remaining` nodes in traversal
+/// order. Returns whether any node was dropped.
+fn limit_nodes(trees: &mut Vec<ascii_tree::Tree>, remaining: &mut usize) -> bool {
+    let mut truncated = false;
+    let mut keep = 0;
+
+    for tree in trees.iter_mut() {
+        if *remaining == 0 {
+            truncated = true;
+            break;
+        }
+        *remaining -= 1;
+        keep += 1;
+
+        if let ascii_tree::Tree::Node(_, children) = tree {
+            truncated |= limit_nodes(children, remaining);
+        }
+    }
+    trees.truncate(keep);
+
+    truncated
+}
+
+/// A line of the rendered tree.
+struct TreeLine {
+    /// The depth of the node starting on this line, or `None` for
+    /// continuation lines and separators.
+    depth: Option<usize>,
+    /// The connector glyphs in front of the text.
+    prefix: String,
+    text: String,
+}
+
+/// The connector glyphs of one level, each `width` columns wide.
+pub(crate) struct Glyphs {
+    pub empty: String,
+    pub edge: String,
+    pub pipe: String,
+    pub branch: String,
+}
+
+impl Default for Glyphs {
+    /// The glyphs for the default indentation width of 3, like ` ├─`.
+    fn default() -> Self {
+        Glyphs::new(TreeConfig::default().indent_width)
+    }
+}
+
+impl Glyphs {
+    /// Builds the glyphs for the indentation width, like ` ├─` for 3,
+    /// `├─` for 2 or `├` for 1. Widths below 1 are treated as 1.
+    pub fn new(width: usize) -> Self {
+        let width = width.max(1);
+        let pad = if width >= 3 { " " } else { "" };
+        let rest = width - pad.len() - 1;
+        let segment = |glyph: char, fill: &str| format!("{}{}{}", pad, glyph, fill.repeat(rest));
+
+        Glyphs {
+            empty: " ".repeat(width),
+            edge: segment('└', "─"),
+            pipe: segment('│', " "),
+            branch: segment('├', "─"),
+        }
+    }
+}
+
+/// Describes the labels and the structure of the tree, so equal subtrees
+/// have equal keys.
+fn subtree_key(tree: &ascii_tree::Tree) -> String {
+    match tree {
+        ascii_tree::Tree::Node(title, children) => {
+            let children: Vec<_> = children.iter().map(subtree_key).collect();
+            format!("{:?}({})", title, children.join(","))
+        }
+        ascii_tree::Tree::Leaf(lines) => format!("{:?}", lines),
+    }
+}
+
+/// Lays out the `tree` as lines, with `levels` telling for the node and
+/// each of its ancestors whether it is the last of its siblings.
+///
+/// With `seen` set, a node equal to an already laid out one refers to the
+/// line of the first one instead of repeating its children.
+fn layout(
+    tree: &ascii_tree::Tree,
+    glyphs: &Glyphs,
+    levels: &mut Vec<bool>,
+    depth: usize,
+    lines: &mut Vec<TreeLine>,
+    seen: &mut Option<HashMap<String, usize>>,
+) {
+    let mut first_prefix = String::new();
+    let mut continuation_prefix = String::new();
+    for (i, &last) in levels.iter().enumerate() {
+        let own_level = i + 1 == levels.len();
+        first_prefix.push_str(match (own_level, last) {
+            (false, false) => &glyphs.pipe,
+            (false, true) => &glyphs.empty,
+            (true, false) => &glyphs.branch,
+            (true, true) => &glyphs.edge,
+        });
+        continuation_prefix.push_str(if last { &glyphs.empty } else { &glyphs.pipe });
+    }
+
+    match tree {
+        ascii_tree::Tree::Node(title, children) => {
+            if let Some(seen) = seen {
+                if !children.is_empty() {
+                    let key = subtree_key(tree);
+                    if let Some(line) = seen.get(&key) {
+                        lines.push(TreeLine {
+                            depth: Some(depth),
+                            prefix: first_prefix,
+                            text: format!(" {} ↑ same as line {}", title, line),
+                        });
+                        return;
+                    }
+                    seen.insert(key, lines.len() + 1);
+                }
+            }
+
+            lines.push(TreeLine {
+                depth: Some(depth),
+                prefix: first_prefix,
+                text: format!(" {}", title),
+            });
+            for (i, child) in children.iter().enumerate() {
+                levels.push(i + 1 == children.len());
+                layout(child, glyphs, levels, depth + 1, lines, seen);
+                levels.pop();
+            }
+        }
+        ascii_tree::Tree::Leaf(leaf_lines) => {
+            for (i, line) in leaf_lines.iter().enumerate() {
+                lines.push(if i == 0 {
+                    TreeLine {
+                        depth: Some(depth),
+                        prefix: first_prefix.clone(),
+                        text: format!(" {}", line),
+                    }
+                } else {
+                    TreeLine {
+                        depth: None,
+                        prefix: continuation_prefix.clone(),
+                        text: format!(" {}", line),
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Lays out all trees. Multiple trees are rendered as siblings without a
+/// common root line, optionally separated by the root separator.
+fn layout_roots(trees: &[ascii_tree::Tree], config: &TreeConfig, dedup: bool) -> Vec<TreeLine> {
+    let mut lines = Vec::new();
+    let mut seen = if dedup { Some(HashMap::new()) } else { None };
+    let glyphs = Glyphs::new(config.indent_width);
+
+    if let [tree] = trees {
+        layout(tree, &glyphs, &mut Vec::new(), 0, &mut lines, &mut seen);
+        return lines;
+    }
+
+    if let MultiRootStyle::NamedRoot(name) = &config.multi_root {
+        if !trees.is_empty() {
+            lines.push(TreeLine {
+                depth: Some(0),
+                prefix: String::new(),
+                text: format!(" {}", name),
+            });
+        }
+    }
+
+    for (i, tree) in trees.iter().enumerate() {
+        let named_root = matches!(config.multi_root, MultiRootStyle::NamedRoot(_));
+        if i > 0 && !named_root {
+            if let Some(separator) = &config.root_separator {
+                lines.push(TreeLine {
+                    depth: None,
+                    prefix: String::new(),
+                    text: separator.clone(),
+                });
+            }
+        }
+
+        let last = i + 1 == trees.len();
+        match &config.multi_root {
+            MultiRootStyle::Flatten => {
+                layout(tree, &glyphs, &mut vec![last], 0, &mut lines, &mut seen)
+            }
+            MultiRootStyle::NumberedRoots => {
+                lines.push(TreeLine {
+                    depth: Some(0),
+                    prefix: String::new(),
+                    text: format!(" [root {}]", i),
+                });
+                layout(tree, &glyphs, &mut vec![true], 1, &mut lines, &mut seen);
+            }
+            MultiRootStyle::NamedRoot(_) => {
+                layout(tree, &glyphs, &mut vec![last], 1, &mut lines, &mut seen)
+            }
+        }
+    }
+
+    lines
+}
+
+/// Shortens the line to at most `max_width` columns: first the content
+/// of a leaf is truncated, then the leading segments of the prefix are
+/// elided and finally the text itself is cut.
+fn fit_line(line: &mut TreeLine, max_width: usize, segment_width: usize) {
+    let line_width =
+        |line: &TreeLine| width::display_width(&line.prefix) + width::display_width(&line.text);
+    if line_width(line) <= max_width {
+        return;
+    }
+
+    // leaf texts look like ` val "content"`,
+    // or just ` "content"` on their own line.
+    let content_start = match line.text.strip_prefix(' ') {
+        Some(text) if text.starts_with('"') => Some(2),
+        Some(text) => text.find(" \"").map(|i| i + 3),
+        None => None,
+    };
+    if let Some(start) = content_start {
+        if line.text.len() > start && line.text.ends_with('"') {
+            let head = &line.text[..start];
+            let content = &line.text[start..line.text.len() - 1];
+            let budget = max_width
+                .saturating_sub(width::display_width(&line.prefix) + width::display_width(head) + 1)
+                .max(1);
+            line.text = format!("{}{}\"", head, width::truncate(content, budget));
+        }
+    }
+
+    let mut segments: Vec<String> = line
+        .prefix
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(segment_width)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    let mut elided = false;
+    while line_width(line) > max_width && segments.len() > 1 {
+        segments.remove(0);
+        elided = true;
+        line.prefix = format!("…{}", segments.concat());
+    }
+    if elided && line_width(line) > max_width {
+        line.prefix = segments.concat();
+    }
+
+    let budget = max_width.saturating_sub(width::display_width(&line.prefix));
+    line.text = width::truncate(&line.text, budget);
+}
+
+/// The depth from which on [`TreeConfig::depth_gradient`] doesn't get
+/// any darker.
+const GRADIENT_MAX_DEPTH: usize = 7;
+
+/// Whether colors are disabled by the `NO_COLOR` environment variable,
+/// see <https://no-color.org>.
+pub(crate) fn no_color() -> bool {
+    matches!(std::env::var_os("NO_COLOR"), Some(value) if !value.is_empty())
+}
+
+/// The escape sequence setting the background of a line at the `depth`,
+/// from gray 240 at the roots down to 233.
+fn depth_background(depth: usize) -> String {
+    format!("\x1b[48;5;{}m", 240 - depth.min(GRADIENT_MAX_DEPTH))
+}
+
+fn write_lines(output: &mut String, lines: &mut [TreeLine], config: &TreeConfig) -> fmt::Result {
+    let gutter_width = if config.show_depth_gutter {
+        let max_depth = lines.iter().filter_map(|line| line.depth).max();
+        Some(max_depth.unwrap_or_default().to_string().len())
+    } else {
+        None
+    };
+
+    let gradient = config.depth_gradient && !no_color();
+    let mut depth = 0;
+    for line in lines {
+        depth = line.depth.unwrap_or(depth);
+        // separators are the only lines not starting with a space.
+        let separator =
+            line.depth.is_none() && line.prefix.is_empty() && !line.text.starts_with(' ');
+        if let (Some(max_width), false) = (config.max_line_width, separator) {
+            let gutter = gutter_width.map_or(0, |width| width + 1);
+            fit_line(
+                line,
+                max_width.saturating_sub(gutter),
+                config.indent_width.max(1),
+            );
+        }
+        match (gutter_width, line.depth) {
+            (Some(width), Some(depth)) => write!(output, "{:>width$}│", depth, width = width)?,
+            (Some(width), None) => write!(output, "{:width$}│", "", width = width)?,
+            (None, _) => {}
+        }
+        if gradient {
+            writeln!(
+                output,
+                "{}{}{}\x1b[0m",
+                depth_background(depth),
+                line.prefix,
+                line.text
+            )?;
+        } else {
+            writeln!(output, "{}{}", line.prefix, line.text)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn reverse_children(trees: &mut [ascii_tree::Tree]) {
+    trees.reverse();
+    for tree in trees {
+        if let ascii_tree::Tree::Node(_, children) = tree {
+            reverse_children(children);
+        }
+    }
+}
+
+/// Mirrors the laid out lines vertically, so the root ends up at the
+/// bottom.
+fn invert(lines: &mut Vec<TreeLine>) {
+    lines.reverse();
+    for line in lines {
+        line.prefix = line.prefix.replace('└', "┌");
+    }
+}
+
+/// The connector glyph pointing in the opposite horizontal direction.
+fn mirror_glyph(glyph: char) -> char {
+    match glyph {
+        '├' => '┤',
+        '└' => '┘',
+        '┌' => '┐',
+        glyph => glyph,
+    }
+}
+
+/// Mirrors the laid out lines horizontally for right-to-left scripts.
+/// The connectors are reversed and put behind the text, the content of
+/// a line moves in front of its rule name and all lines are aligned to
+/// the right, without trailing whitespace.
+fn mirror(lines: &mut [TreeLine]) {
+    for line in lines.iter_mut() {
+        let text = match line.text.strip_prefix(' ') {
+            Some(text) => match text.find(" \"") {
+                Some(i) => format!("{} {} ", &text[i + 1..], &text[..i]),
+                None => format!("{} ", text),
+            },
+            None => line.text.clone(),
+        };
+        let prefix: String = line.prefix.chars().rev().map(mirror_glyph).collect();
+        line.text = text + &prefix;
+        line.prefix = String::new();
+    }
+
+    let max_width = lines
+        .iter()
+        .map(|line| width::display_width(&line.text))
+        .max()
+        .unwrap_or_default();
+    for line in lines {
+        line.prefix = " ".repeat(max_width - width::display_width(&line.text));
+        line.text.truncate(line.text.trim_end().len());
+    }
+}
+
+/// Writes the nodes as ascii tree into `output`, which is cleared first.
+/// Multiple nodes are rendered as siblings without a common root line.
+pub(crate) fn render_into(
+    nodes: &[ParseTreeNode],
+    config: &TreeConfig,
+    output: &mut String,
+) -> fmt::Result {
+    Renderer::new(config).render_into(nodes, output)
+}
+
+impl<'a> Renderer<'a> {
+    /// Writes the nodes as ascii tree into `output`, which is cleared
+    /// first.
+    pub fn render_into(&self, nodes: &[ParseTreeNode], output: &mut String) -> fmt::Result {
+        let config = self.config;
+        output.clear();
+
+        let mut longest: Option<(usize, Vec<usize>)> = None;
+        if config.highlight_longest_leaf && self.highlighted.is_none() {
+            self.visit_all_leaves(nodes, &mut |node, _, ids| {
+                let len = self.leaf_text(node).chars().count();
+                if !matches!(&longest, Some((max, _)) if *max >= len) {
+                    longest = Some((len, ids.to_vec()));
+                }
+            });
+        }
+        let mut renderer = Renderer {
+            highlighted: self
+                .highlighted
+                .or_else(|| longest.as_ref().map(|(_, ids)| &ids[..])),
+            ..*self
+        };
+
+        if config.global_align
+            && renderer.align_column.is_none()
+            && !config.content_on_new_line
+            && !config.hide_all_content
+        {
+            let mut column = 0;
+            renderer.visit_all_leaves(nodes, &mut |node, path, ids| {
+                let head = format!(
+                    "{}{}",
+                    renderer.line_head(node, ids),
+                    renderer.leaf_label(node, path)
+                );
+                let indent = path.len() * config.indent_width.max(1);
+                column = column.max(indent + width::display_width(&head));
+            });
+            renderer.align_column = Some(column);
+        }
+
+        renderer.render_prepared(nodes, output)
+    }
+
+    /// Writes the nodes like [`Renderer::render_into`], after the passes
+    /// over the whole tree are done.
+    fn render_prepared(&self, nodes: &[ParseTreeNode], output: &mut String) -> fmt::Result {
+        let config = self.config;
+
+        let mut roots: Vec<_> = nodes.iter().enumerate().collect();
+        tree::sort_sections(&mut roots, config.forest_order, |(_, node)| {
+            std::slice::from_ref(*node)
+        });
+        let mut trees: Vec<_> = roots
+            .into_iter()
+            .map(|(i, node)| self.tree_node(node, i))
+            .collect();
+
+        let mut truncated = false;
+        if let Some(max_nodes) = config.max_nodes {
+            let mut remaining = max_nodes;
+            truncated = limit_nodes(&mut trees, &mut remaining);
+        }
+
+        if trees.is_empty() && !truncated {
+            if let Some(placeholder) = &config.empty_placeholder {
+                writeln!(output, "{}", placeholder)?;
+            }
+        }
+        let mut lines = if self.inverted {
+            reverse_children(&mut trees);
+            let mut lines = layout_roots(&trees, config, false);
+            invert(&mut lines);
+            lines
+        } else {
+            layout_roots(&trees, config, config.dedup_subtrees)
+        };
+        if config.rtl {
+            mirror(&mut lines);
+        }
+        write_lines(output, &mut lines, config)?;
+
+        if truncated {
+            writeln!(
+                output,
+                "… (truncated at {} nodes)",
+                config.max_nodes.unwrap_or_default()
+            )?;
+        }
+
+        if let Some(max_width) = config.abbreviate_rules {
+            self.write_abbreviations(nodes, max_width, output)?;
+        }
+
+        if !config.trailing_newline && output.ends_with('\n') {
+            output.pop();
+        }
+
+        Ok(())
+    }
+
+    /// Writes a legend of the abbreviations by
+    /// [`TreeConfig::abbreviate_rules`] shared by different rules of the
+    /// `nodes`, like `  nu.: null, number`.
+    fn write_abbreviations(
+        &self,
+        nodes: &[ParseTreeNode],
+        max_width: usize,
+        output: &mut String,
+    ) -> fmt::Result {
+        fn collect<'n>(
+            renderer: &Renderer,
+            nodes: &'n [ParseTreeNode],
+            max_width: usize,
+            names: &mut BTreeMap<String, BTreeSet<&'n str>>,
+        ) {
+            for node in nodes {
+                let name = renderer.rule_name(node);
+                let abbreviation = abbreviate(name, max_width).into_owned();
+                names.entry(abbreviation).or_default().insert(name);
+                collect(renderer, &node.children, max_width, names);
+            }
+        }
+
+        let mut names = BTreeMap::new();
+        collect(self, nodes, max_width, &mut names);
+        let mut ambiguous = names.iter().filter(|(_, names)| names.len() > 1).peekable();
+        if ambiguous.peek().is_some() {
+            writeln!(output, "abbreviations:")?;
+        }
+        for (abbreviation, names) in ambiguous {
+            let names: Vec<_> = names.iter().copied().collect();
+            writeln!(output, "  {}: {}", abbreviation, names.join(", "))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the nodes as ascii tree into a new `String`.
+    pub fn render(&self, nodes: &[ParseTreeNode]) -> Result<String, fmt::Error> {
+        let mut output = String::new();
+        self.render_into(nodes, &mut output)?;
+
+        Ok(output)
+    }
+}
+
+/// Writes the nodes as ascii tree into a new `String`.
+pub(crate) fn render(nodes: &[ParseTreeNode], config: &TreeConfig) -> Result<String, fmt::Error> {
+    Renderer::new(config).render(nodes)
+}
+
+/// Writes already converted trees as ascii tree into a new `String`.
+pub(crate) fn render_trees(
+    trees: &[ascii_tree::Tree],
+    config: &TreeConfig,
+) -> Result<String, fmt::Error> {
+    let mut output = String::new();
+    let mut lines = layout_roots(trees, config, false);
+    write_lines(&mut output, &mut lines, config)?;
+
+    Ok(output)
+}
@@ -0,0 +1,176 @@
+//! Rendering that reports the limits and anomalies hit along the way.
+
+use crate::config::TreeConfig;
+use crate::error::TreeError;
+use crate::render;
+use crate::tree;
+use pest::iterators::Pairs;
+use std::fmt;
+
+/// Something the reader of a rendered tree should know about, as
+/// collected by [`render_report`].
+///
+/// [`render_report`]: fn.render_report.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// The pairs end before the end of the input.
+    PartialMatch {
+        /// The byte offset the last top-level pair ends at.
+        consumed: usize,
+        /// The length of the input in bytes.
+        len: usize,
+    },
+    /// Nodes deeper than [`TreeConfig::fold_beyond_depth`] were folded.
+    ///
+    /// [`TreeConfig::fold_beyond_depth`]: struct.TreeConfig.html#structfield.fold_beyond_depth
+    DepthLimit(usize),
+    /// Nodes beyond [`TreeConfig::max_nodes`] were left out.
+    ///
+    /// [`TreeConfig::max_nodes`]: struct.TreeConfig.html#structfield.max_nodes
+    NodeLimit(usize),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::PartialMatch { consumed, len } => {
+                write!(f, "input not fully consumed: {} of {} bytes", consumed, len)
+            }
+            Warning::DepthLimit(depth) => {
+                write!(f, "depth limit hit: folded beyond depth {}", depth)
+            }
+            Warning::NodeLimit(max_nodes) => {
+                write!(f, "output truncated: more than {} nodes", max_nodes)
+            }
+        }
+    }
+}
+
+/// A rendered tree together with the warnings collected while rendering
+/// it, returned by [`render_report`].
+///
+/// Its `Display` prints the tree followed by a `warning: …` line per
+/// warning.
+///
+/// [`render_report`]: fn.render_report.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderReport {
+    /// The rendered tree.
+    pub tree: String,
+    /// The limits and anomalies found, in the order they were found.
+    pub warnings: Vec<Warning>,
+}
+
+impl fmt::Display for RenderReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.tree)?;
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the parsing result by pest of the `input` into an ascii_tree
+/// according to the `config`, noting whether the input wasn't fully
+/// consumed or a limit of the `config` was hit.
+///
+/// # Error
+/// If writing the formatted tree failed, the error variant is passed to
+/// the caller.
+///
+/// # Examples
+/// ```ignore
+/// let input = "a + b )";
+/// let report = pest_ascii_tree::render_report(
+///                  input, ExpressionParser::parse(Rule::expr, input)?, &TreeConfig::default())?;
+/// assert_eq!(
+///     report.warnings,
+///     vec![Warning::PartialMatch { consumed: 5, len: 7 }]
+/// );
+/// ```
+pub fn render_report<R>(
+    input: &str,
+    pairs: Pairs<R>,
+    config: &TreeConfig,
+) -> Result<RenderReport, TreeError>
+where
+    R: pest::RuleType,
+{
+    let consumed = pairs.clone().last().map_or(0, |pair| pair.as_span().end());
+    let nodes = tree::build(pairs, config);
+
+    let mut warnings = Vec::new();
+    if consumed != input.len() {
+        warnings.push(Warning::PartialMatch {
+            consumed,
+            len: input.len(),
+        });
+    }
+    if let Some(max_depth) = config.fold_beyond_depth {
+        if tree::depth(&nodes) > max_depth {
+            warnings.push(Warning::DepthLimit(max_depth));
+        }
+    }
+    if let Some(max_nodes) = config.max_nodes {
+        if tree::node_count(&nodes) > max_nodes {
+            warnings.push(Warning::NodeLimit(max_nodes));
+        }
+    }
+
+    Ok(RenderReport {
+        tree: render::render(&nodes, config)?,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn report() {
+        let input = "a + (b) )";
+        let config = TreeConfig {
+            fold_beyond_depth: Some(1),
+            max_nodes: Some(3),
+            ..Default::default()
+        };
+        let report = render_report(
+            input,
+            ExpressionParser::parse(Rule::expr, input).unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            report.warnings,
+            [
+                Warning::PartialMatch {
+                    consumed: 7,
+                    len: 9
+                },
+                Warning::DepthLimit(1),
+                Warning::NodeLimit(3),
+            ]
+        );
+        assert!(report.to_string().ends_with(
+            &(String::new()
+                + "warning: input not fully consumed: 7 of 9 bytes\n"
+                + "warning: depth limit hit: folded beyond depth 1\n"
+                + "warning: output truncated: more than 3 nodes\n")
+        ));
+
+        let input = "a + (b)";
+        let report = render_report(
+            input,
+            ExpressionParser::parse(Rule::expr, input).unwrap(),
+            &TreeConfig::default(),
+        )
+        .unwrap();
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.to_string(), report.tree);
+    }
+}
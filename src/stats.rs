@@ -0,0 +1,157 @@
+//! Statistics about the shape of the parse tree.
+
+use crate::config::TreeConfig;
+use crate::tree::{self, ParseTreeNode};
+use pest::iterators::Pairs;
+use std::collections::BTreeMap;
+
+/// Statistics about the parse tree returned by [`tree_stats`].
+///
+/// [`tree_stats`]: fn.tree_stats.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeStats {
+    /// The number of all nodes.
+    pub node_count: usize,
+    /// The number of nodes without children.
+    pub leaf_count: usize,
+    /// The depth of the deepest node, the top-level nodes being at depth
+    /// 0.
+    pub max_depth: usize,
+    /// How often every rule occurs, sorted by the rule name.
+    pub rule_counts: BTreeMap<String, usize>,
+    /// How often every rule occurs at each depth, starting with the
+    /// top-level nodes.
+    pub level_counts: Vec<BTreeMap<String, usize>>,
+}
+
+impl TreeStats {
+    fn add(&mut self, nodes: &[ParseTreeNode], depth: usize) {
+        for node in nodes {
+            self.node_count += 1;
+            if node.is_leaf() {
+                self.leaf_count += 1;
+            }
+            self.max_depth = self.max_depth.max(depth);
+            *self.rule_counts.entry(node.rule.clone()).or_default() += 1;
+            if self.level_counts.len() <= depth {
+                self.level_counts.push(BTreeMap::new());
+            }
+            *self.level_counts[depth]
+                .entry(node.rule.clone())
+                .or_default() += 1;
+
+            self.add(&node.children, depth + 1);
+        }
+    }
+
+    /// Returns the `n` most frequent rules with their number of
+    /// occurrences. Rules occurring equally often are sorted by name.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let stats = pest_ascii_tree::tree_stats(ExpressionParser::parse(Rule::expr, "a + b")?);
+    /// assert_eq!(stats.top_rules(1), vec![("val".to_string(), 2)]);
+    /// ```
+    pub fn top_rules(&self, n: usize) -> Vec<(String, usize)> {
+        let mut rules: Vec<_> = self
+            .rule_counts
+            .iter()
+            .map(|(rule, &count)| (rule.clone(), count))
+            .collect();
+        // the map is sorted by name already and the sort is stable.
+        rules.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        rules.truncate(n);
+
+        rules
+    }
+}
+
+/// Collects statistics about the parse tree, like the number of nodes and
+/// how often every rule occurs. The `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let stats = pest_ascii_tree::tree_stats(ExpressionParser::parse(Rule::expr, "a + (b)")?);
+/// assert_eq!(stats.node_count, 5);
+/// assert_eq!(stats.max_depth, 2);
+/// ```
+pub fn tree_stats<R>(pairs: Pairs<R>) -> TreeStats
+where
+    R: pest::RuleType,
+{
+    let mut stats = TreeStats::default();
+    stats.add(&tree::build(pairs, &TreeConfig::default()), 0);
+
+    stats
+}
+
+/// Summarizes the parse tree by the rules occurring at each depth, one
+/// line per depth like `L1: op×2, val×3`, instead of rendering the whole
+/// tree. The rules of a line are sorted by name and the `EOI` rule is
+/// skipped.
+///
+/// # Examples
+/// ```ignore
+/// let summary =
+///     pest_ascii_tree::into_level_summary(ExpressionParser::parse(Rule::expr, "a + (b)")?);
+/// assert_eq!(summary, "L0: expr×1\nL1: expr×1, op×1, val×1\nL2: val×1\n");
+/// ```
+pub fn into_level_summary<R>(pairs: Pairs<R>) -> String
+where
+    R: pest::RuleType,
+{
+    let stats = tree_stats(pairs);
+
+    let mut output = String::new();
+    for (depth, counts) in stats.level_counts.iter().enumerate() {
+        let rules: Vec<_> = counts
+            .iter()
+            .map(|(rule, count)| format!("{}×{}", rule, count))
+            .collect();
+        output.push_str(&format!("L{}: {}\n", depth, rules.join(", ")));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn stats() {
+        let stats = tree_stats(ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap());
+        assert_eq!(stats.node_count, 7);
+        assert_eq!(stats.leaf_count, 5);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.rule_counts["val"], 3);
+
+        let top = |n| {
+            stats
+                .top_rules(n)
+                .into_iter()
+                .map(|(rule, count)| format!("{} {}", rule, count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(top(2), ["val 3", "expr 2"]);
+        assert_eq!(top(10), ["val 3", "expr 2", "op 2"]);
+    }
+
+    #[test]
+    fn level_summary() {
+        let summary =
+            into_level_summary(ExpressionParser::parse(Rule::expr, "a + (b - c)").unwrap());
+        assert_eq!(
+            summary,
+            String::new() + "L0: expr×1\n" + "L1: expr×1, op×1, val×1\n" + "L2: op×1, val×2\n"
+        );
+
+        let summary = into_level_summary(ExpressionParser::parse(Rule::statement, "a;").unwrap());
+        assert_eq!(
+            summary,
+            "L0: statement×1\nL1: expr×1, terminator×1\nL2: val×1\n"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Rendering directly from the [`Pairs`] iterator without building the
+//! intermediate tree first.
+//!
+//! [`Pairs`]: https://docs.rs/pest/2/pest/iterators/struct.Pairs.html
+
+use crate::config::TreeConfig;
+use crate::render::{self, Glyphs};
+use crate::tree;
+use pest::iterators::{Pair, Pairs};
+use std::fmt::{self, Write};
+
+/// Whether the pair is left out of the tree, like `EOI` is by
+/// [`into_ascii_tree`](fn.into_ascii_tree.html).
+fn is_skipped<R>(config: &TreeConfig, pair: &Pair<R>) -> bool
+where
+    R: pest::RuleType,
+{
+    config.is_skipped(&tree::rule_name(pair.as_rule()))
+}
+
+fn rule_label<R>(pair: &Pair<R>) -> String
+where
+    R: pest::RuleType,
+{
+    render::escape_rule_name(&tree::rule_name(pair.as_rule()))
+}
+
+/// The state shared while walking the pairs.
+struct Walk<'w, W> {
+    w: &'w mut W,
+    glyphs: Glyphs,
+    /// The glyphs in front of the current pair.
+    prefix: String,
+}
+
+impl<'w, W> Walk<'w, W>
+where
+    W: Write,
+{
+    fn new(w: &'w mut W) -> Self {
+        Walk {
+            w,
+            glyphs: Glyphs::default(),
+            prefix: String::new(),
+        }
+    }
+
+    /// Writes the pair with all its inner pairs. `last` tells whether the
+    /// pair is the last of its siblings, or is `None` for a single root.
+    /// Pairs skipped by the `config` are left out.
+    fn write_pair<R>(
+        &mut self,
+        config: &TreeConfig,
+        pair: Pair<R>,
+        last: Option<bool>,
+    ) -> fmt::Result
+    where
+        R: pest::RuleType,
+    {
+        self.w.write_str(&self.prefix)?;
+        match last {
+            Some(true) => self.w.write_str(&self.glyphs.edge)?,
+            Some(false) => self.w.write_str(&self.glyphs.branch)?,
+            None => {}
+        }
+
+        let inner = pair.clone().into_inner();
+        if inner.clone().any(|pair| !is_skipped(config, &pair)) {
+            writeln!(self.w, " {}", rule_label(&pair))?;
+
+            let len = self.prefix.len();
+            match last {
+                Some(true) => self.prefix.push_str(&self.glyphs.empty),
+                Some(false) => self.prefix.push_str(&self.glyphs.pipe),
+                None => {}
+            }
+            self.write_siblings(config, inner)?;
+            self.prefix.truncate(len);
+        } else {
+            writeln!(
+                self.w,
+                " {} \"{}\"",
+                rule_label(&pair),
+                render::default_escape(pair.as_str().trim())
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_siblings<R>(&mut self, config: &TreeConfig, pairs: Pairs<R>) -> fmt::Result
+    where
+        R: pest::RuleType,
+    {
+        let mut pairs = pairs.filter(|pair| !is_skipped(config, pair)).peekable();
+
+        while let Some(pair) = pairs.next() {
+            let last = pairs.peek().is_none();
+            self.write_pair(config, pair, Some(last))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the parsing result by pest as ascii tree into `w` while walking
+/// the pairs.
+///
+/// The output is the same as the one of [`into_ascii_tree`], but no
+/// intermediate tree is built, so the memory needed besides the output
+/// only grows with the depth of the tree. Writing into a sink which
+/// doesn't keep the output, like a buffered stdout, keeps the memory
+/// usage low even for huge trees.
+///
+/// # Examples
+/// ```ignore
+/// let mut output = String::new();
+/// pest_ascii_tree::write_ascii_tree_streaming(
+///     &mut output, ExpressionParser::parse(Rule::expr, "a + b")?)?;
+/// ```
+///
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+pub fn write_ascii_tree_streaming<W, R>(w: &mut W, pairs: Pairs<R>) -> fmt::Result
+where
+    W: Write,
+    R: pest::RuleType,
+{
+    let mut walk = Walk::new(w);
+    let config = TreeConfig::default();
+    let mut pairs = pairs.filter(|pair| !is_skipped(&config, pair)).peekable();
+
+    if let Some(first) = pairs.next() {
+        if pairs.peek().is_none() {
+            walk.write_pair(&config, first, None)?;
+        } else {
+            walk.write_pair(&config, first, Some(false))?;
+            while let Some(pair) = pairs.next() {
+                let last = pairs.peek().is_none();
+                walk.write_pair(&config, pair, Some(last))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the parsing result by pest as ascii tree, passing every
+/// top-level tree to `emit` as soon as it's rendered.
+///
+/// Joining the emitted strings results in the same output as the one of
+/// [`into_ascii_tree`]. Like [`write_ascii_tree_streaming`], no
+/// intermediate tree is built, so a UI can show the first trees while the
+/// later ones are still being rendered.
+///
+/// # Examples
+/// ```ignore
+/// let mut roots = Vec::new();
+/// pest_ascii_tree::render_roots_streaming(
+///     ExpressionParser::parse(Rule::expr_root, "x + y")?, |root| roots.push(root));
+/// assert_eq!(roots, [" ├─ val \"x\"\n", " ├─ op \"+\"\n", " └─ val \"y\"\n"]);
+/// ```
+///
+/// [`into_ascii_tree`]: fn.into_ascii_tree.html
+/// [`write_ascii_tree_streaming`]: fn.write_ascii_tree_streaming.html
+pub fn render_roots_streaming<R, F>(pairs: Pairs<R>, mut emit: F)
+where
+    R: pest::RuleType,
+    F: FnMut(String),
+{
+    let config = TreeConfig::default();
+    let mut pairs = pairs.filter(|pair| !is_skipped(&config, pair)).peekable();
+    let single = pairs.clone().nth(1).is_none();
+
+    while let Some(pair) = pairs.next() {
+        let last = if single {
+            None
+        } else {
+            Some(pairs.peek().is_none())
+        };
+
+        // writing into a `String` never fails.
+        let mut output = String::new();
+        let _ = Walk::new(&mut output).write_pair(&config, pair, last);
+        emit(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn same_as_into_ascii_tree() {
+        let inputs = [
+            (Rule::expr, "a + b + c"),
+            (Rule::expr_root, "x + y + z"),
+            (Rule::val, "m"),
+            (Rule::expr, "(u + (v + w)) + (x + y) + z"),
+            (Rule::root, "(u + (v + w)) + (x + y) + z"),
+            (Rule::expr_root, "(u + (v + w)) + (x + y) + z"),
+            (Rule::statement, "((a)) - b;"),
+        ];
+
+        for &(rule, input) in inputs.iter() {
+            let mut output = String::new();
+            write_ascii_tree_streaming(&mut output, ExpressionParser::parse(rule, input).unwrap())
+                .unwrap();
+            assert_eq!(
+                output,
+                crate::into_ascii_tree(ExpressionParser::parse(rule, input).unwrap()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn roots_streaming() {
+        let mut roots = Vec::new();
+        render_roots_streaming(
+            ExpressionParser::parse(Rule::expr_root, "x + (y)").unwrap(),
+            |root| roots.push(root),
+        );
+        assert_eq!(
+            roots,
+            [
+                " ├─ val \"x\"\n",
+                " ├─ op \"+\"\n",
+                " └─ expr\n    └─ val \"y\"\n"
+            ]
+        );
+
+        let mut roots = Vec::new();
+        render_roots_streaming(
+            ExpressionParser::parse(Rule::root, "a + b").unwrap(),
+            |root| roots.push(root),
+        );
+        assert_eq!(
+            roots.concat(),
+            crate::into_ascii_tree(ExpressionParser::parse(Rule::root, "a + b").unwrap()).unwrap()
+        );
+        assert_eq!(roots.len(), 1);
+    }
+
+    #[cfg(feature = "pest_vm")]
+    #[test]
+    fn same_as_into_ascii_tree_for_str_rules() {
+        let grammar = "expr = { SOI ~ val ~ (op ~ val)* ~ EOI }\n\
+                       val = { ASCII_ALPHA+ }\n\
+                       op = { \"+\" }\n\
+                       WHITESPACE = _{ \" \" }";
+        let (_, rules) = pest_meta::parse_and_optimize(grammar).unwrap();
+        let vm = pest_vm::Vm::new(rules);
+
+        let mut output = String::new();
+        write_ascii_tree_streaming(&mut output, vm.parse("expr", "a + b").unwrap()).unwrap();
+        assert_eq!(
+            output,
+            crate::into_ascii_tree(vm.parse("expr", "a + b").unwrap()).unwrap()
+        );
+        assert_eq!(
+            output,
+            String::new() + " expr\n" + " ├─ val \"a\"\n" + " ├─ op \"+\"\n" + " └─ val \"b\"\n"
+        );
+    }
+}
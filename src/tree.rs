@@ -0,0 +1,569 @@
+use crate::config::{ForestOrder, TreeConfig};
+use pest::{
+    iterators::{Pair, Pairs, Tokens},
+    Position, Span, Token,
+};
+use std::cmp::Ordering;
+
+/// A node of the parse tree with the rule already formatted into its
+/// name.
+///
+/// The nodes are obtained from a [`ParseTree`].
+///
+/// [`ParseTree`]: struct.ParseTree.html
+#[derive(Clone, Debug)]
+pub struct ParseTreeNode<'i> {
+    pub(crate) rule: String,
+    pub(crate) span: Span<'i>,
+    pub(crate) children: Vec<ParseTreeNode<'i>>,
+    /// The node had inner pairs, but all of them were filtered out and
+    /// [`TreeConfig::mark_emptied_nodes`] is set.
+    pub(crate) emptied: bool,
+    /// Extra information shown in brackets behind the node.
+    pub(crate) annotation: Option<String>,
+    /// The contents of the following sibling leaves of the same rule
+    /// merged into this leaf by [`TreeConfig::merge_same_rule_leaves`].
+    pub(crate) merged: Vec<&'i str>,
+    /// The status shown in front of the node.
+    pub(crate) status: Option<Status>,
+}
+
+/// The status of a node shown by [`into_ascii_tree_with_status`] in front
+/// of it.
+///
+/// [`into_ascii_tree_with_status`]: fn.into_ascii_tree_with_status.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Shown as `✅`.
+    Ok,
+    /// Shown as `⚠️`.
+    Warn,
+    /// Shown as `❌`.
+    Error,
+}
+
+impl Status {
+    /// The glyph the status is shown as.
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Status::Ok => "✅",
+            Status::Warn => "⚠️",
+            Status::Error => "❌",
+        }
+    }
+}
+
+impl<'i> ParseTreeNode<'i> {
+    /// Whether the node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty() && !self.emptied
+    }
+
+    /// The matched text without surrounding whitespace.
+    pub fn content(&self) -> &'i str {
+        self.span.as_str().trim()
+    }
+
+    /// The name of the rule, as formatted by `Debug`.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// The span of the matched text in the input.
+    pub fn span(&self) -> &Span<'i> {
+        &self.span
+    }
+
+    /// The direct children of the node.
+    pub fn children(&self) -> &[ParseTreeNode<'i>] {
+        &self.children
+    }
+
+    /// All nodes below this node in traversal order, i.e. every node
+    /// followed by its descendants.
+    pub fn descendants(&self) -> Nodes<'_, 'i> {
+        Nodes::new(&self.children)
+    }
+}
+
+/// The parsing result by pest converted into a tree of
+/// [`ParseTreeNode`]s, to look up nodes by their rule without using the
+/// `Pairs` API.
+///
+/// Like in the ascii tree, the `EOI` rule is skipped.
+///
+/// # Examples
+/// ```ignore
+/// let tree = pest_ascii_tree::ParseTree::new(ExpressionParser::parse(Rule::expr, "a + (b)")?);
+/// let vals: Vec<_> = tree.find_all("val").iter().map(|node| node.content()).collect();
+/// assert_eq!(vals, ["a", "b"]);
+/// assert_eq!(tree.first("op").unwrap().content(), "+");
+/// ```
+///
+/// [`ParseTreeNode`]: struct.ParseTreeNode.html
+#[derive(Clone, Debug)]
+pub struct ParseTree<'i> {
+    roots: Vec<ParseTreeNode<'i>>,
+}
+
+impl<'i> ParseTree<'i> {
+    /// Converts the pairs into the parse tree.
+    pub fn new<R>(pairs: Pairs<'i, R>) -> Self
+    where
+        R: pest::RuleType,
+    {
+        Self::with_config(pairs, &TreeConfig::default())
+    }
+
+    /// Converts the pairs into the parse tree, applying the options of
+    /// the `config` changing the structure of the tree, like
+    /// [`TreeConfig::skip_rules`].
+    ///
+    /// [`TreeConfig::skip_rules`]: struct.TreeConfig.html#structfield.skip_rules
+    pub fn with_config<R>(pairs: Pairs<'i, R>, config: &TreeConfig) -> Self
+    where
+        R: pest::RuleType,
+    {
+        ParseTree {
+            roots: build(pairs, config),
+        }
+    }
+
+    /// The top-level nodes.
+    pub fn roots(&self) -> &[ParseTreeNode<'i>] {
+        &self.roots
+    }
+
+    /// All nodes in traversal order.
+    pub fn iter(&self) -> Nodes<'_, 'i> {
+        Nodes::new(&self.roots)
+    }
+
+    /// All nodes of the rule named `rule` in traversal order.
+    pub fn find_all(&self, rule: &str) -> Vec<&ParseTreeNode<'i>> {
+        self.iter().filter(|node| node.rule == rule).collect()
+    }
+
+    /// The first node of the rule named `rule` in traversal order.
+    pub fn first(&self, rule: &str) -> Option<&ParseTreeNode<'i>> {
+        find(&self.roots, &|node: &ParseTreeNode| node.rule == rule)
+    }
+}
+
+impl<'a, 'i> IntoIterator for &'a ParseTree<'i> {
+    type Item = &'a ParseTreeNode<'i>;
+    type IntoIter = Nodes<'a, 'i>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over nodes and their descendants in traversal order,
+/// returned by [`ParseTree::iter`] and [`ParseTreeNode::descendants`].
+///
+/// [`ParseTree::iter`]: struct.ParseTree.html#method.iter
+/// [`ParseTreeNode::descendants`]: struct.ParseTreeNode.html#method.descendants
+#[derive(Clone, Debug)]
+pub struct Nodes<'a, 'i> {
+    /// The remaining siblings of every level, the innermost last.
+    stack: Vec<std::slice::Iter<'a, ParseTreeNode<'i>>>,
+}
+
+impl<'a, 'i> Nodes<'a, 'i> {
+    fn new(nodes: &'a [ParseTreeNode<'i>]) -> Self {
+        Nodes {
+            stack: vec![nodes.iter()],
+        }
+    }
+}
+
+impl<'a, 'i> Iterator for Nodes<'a, 'i> {
+    type Item = &'a ParseTreeNode<'i>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(siblings) = self.stack.last_mut() {
+            if let Some(node) = siblings.next() {
+                self.stack.push(node.children.iter());
+                return Some(node);
+            }
+            self.stack.pop();
+        }
+
+        None
+    }
+}
+
+/// The number of nodes in the `nodes` and all their descendants.
+pub(crate) fn node_count(nodes: &[ParseTreeNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| 1 + node_count(&node.children))
+        .sum()
+}
+
+/// The depth of the deepest node, the `nodes` being at depth 0.
+pub(crate) fn depth(nodes: &[ParseTreeNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| {
+            if node.children.is_empty() {
+                0
+            } else {
+                1 + depth(&node.children)
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Sorts the `sections`, whose top-level nodes are returned by `nodes`,
+/// in the `order`. The sort is stable, so equal sections keep their
+/// order.
+pub(crate) fn sort_sections<'i, T>(
+    sections: &mut [T],
+    order: ForestOrder,
+    nodes: for<'a> fn(&'a T) -> &'a [ParseTreeNode<'i>],
+) {
+    match order {
+        ForestOrder::Input => {}
+        ForestOrder::NodeCount => {
+            sections.sort_by_key(|section| std::cmp::Reverse(node_count(nodes(section))))
+        }
+        ForestOrder::RuleName => sections.sort_by(|a, b| {
+            let rule = |section| nodes(section).first().map(|node| node.rule.as_str());
+            rule(a).cmp(&rule(b))
+        }),
+    }
+}
+
+/// Keeps the nodes for which `predicate` is `true` together with their
+/// ancestors and drops all others.
+pub(crate) fn retain<'i>(
+    nodes: Vec<ParseTreeNode<'i>>,
+    predicate: &dyn Fn(&ParseTreeNode<'i>) -> bool,
+) -> Vec<ParseTreeNode<'i>> {
+    nodes
+        .into_iter()
+        .filter_map(|mut node| {
+            node.children = retain(std::mem::take(&mut node.children), predicate);
+            if node.children.is_empty() && !predicate(&node) {
+                None
+            } else {
+                Some(node)
+            }
+        })
+        .collect()
+}
+
+/// Finds the first node in traversal order for which `predicate` is
+/// `true`.
+pub(crate) fn find<'a, 'i, F>(
+    nodes: &'a [ParseTreeNode<'i>],
+    predicate: &F,
+) -> Option<&'a ParseTreeNode<'i>>
+where
+    F: Fn(&ParseTreeNode<'i>) -> bool,
+{
+    for node in nodes {
+        if predicate(node) {
+            return Some(node);
+        }
+        if let Some(found) = find(&node.children, predicate) {
+            return Some(found);
+        }
+    }
+
+    None
+}
+
+/// Converts the pairs into the parse tree, skipping the `EOI` rule as well
+/// as the rules in [`TreeConfig::skip_rules`] and
+/// applying the structural options of the `config`.
+pub(crate) fn build<'i, R>(pairs: Pairs<'i, R>, config: &TreeConfig) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    finish(build_nodes(pairs, config, &|_, _| {}, None), config)
+}
+
+/// Sets extra information of the node built from the pair.
+type Decorate<'a, 'i, R> = dyn Fn(&Pair<'i, R>, &mut ParseTreeNode<'i>) + 'a;
+
+/// Like [`build`], but annotates every node with the result of
+/// `annotate` for its pair.
+pub(crate) fn build_annotated<'i, R>(
+    pairs: Pairs<'i, R>,
+    config: &TreeConfig,
+    annotate: &dyn Fn(&Pair<'i, R>) -> Option<String>,
+) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    let decorate = |pair: &Pair<'i, R>, node: &mut ParseTreeNode<'i>| {
+        node.annotation = annotate(pair);
+    };
+    finish(build_nodes(pairs, config, &decorate, None), config)
+}
+
+/// Like [`build`], but sets the status of every node to the result of
+/// `status` for its pair.
+pub(crate) fn build_with_status<'i, R>(
+    pairs: Pairs<'i, R>,
+    config: &TreeConfig,
+    status: &dyn Fn(&Pair<'i, R>) -> Status,
+) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    let decorate = |pair: &Pair<'i, R>, node: &mut ParseTreeNode<'i>| {
+        node.status = Some(status(pair));
+    };
+    finish(build_nodes(pairs, config, &decorate, None), config)
+}
+
+/// Compares two sibling pairs for [`build_sorted`].
+type PairOrder<'a, 'i, R> = dyn Fn(&Pair<'i, R>, &Pair<'i, R>) -> Ordering + 'a;
+
+/// Like [`build`], but sorts the siblings at every level by `cmp`. The
+/// sort is stable, so siblings comparing equal keep their order.
+pub(crate) fn build_sorted<'i, R>(
+    pairs: Pairs<'i, R>,
+    config: &TreeConfig,
+    cmp: &PairOrder<'_, 'i, R>,
+) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    finish(build_nodes(pairs, config, &|_, _| {}, Some(cmp)), config)
+}
+
+/// Like [`build`], but reconstructs the nesting from the matching
+/// `Start` and `End` tokens.
+///
+/// An `End` token closes the innermost open rule. Rules still open at
+/// the end of the stream are closed at the position of the last token.
+pub(crate) fn build_from_tokens<'i, R>(
+    tokens: Tokens<'i, R>,
+    config: &TreeConfig,
+) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    let mut roots = Vec::new();
+    let mut open: Vec<OpenRule<'i>> = Vec::new();
+    let mut last_pos = None;
+
+    let mut close = |open: &mut Vec<OpenRule<'i>>, end: &Position<'i>| {
+        if let Some(OpenRule {
+            rule,
+            start,
+            children,
+            had_inner,
+        }) = open.pop()
+        {
+            if config.is_skipped(&rule) {
+                return;
+            }
+            let node = ParseTreeNode {
+                rule,
+                span: start.span(end),
+                emptied: config.mark_emptied_nodes && had_inner && children.is_empty(),
+                children,
+                annotation: None,
+                merged: Vec::new(),
+                status: None,
+            };
+            if is_hidden(&node, config) {
+                return;
+            }
+            match open.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        }
+    };
+
+    for token in tokens {
+        match token {
+            Token::Start { rule, pos } => {
+                last_pos = Some(pos);
+                if let Some(parent) = open.last_mut() {
+                    parent.had_inner = true;
+                }
+                open.push(OpenRule {
+                    rule: rule_name(rule),
+                    start: pos,
+                    children: Vec::new(),
+                    had_inner: false,
+                });
+            }
+            Token::End { pos, .. } => {
+                close(&mut open, &pos);
+                last_pos = Some(pos);
+            }
+        }
+    }
+    if let Some(end) = last_pos {
+        while !open.is_empty() {
+            close(&mut open, &end);
+        }
+    }
+
+    finish(roots, config)
+}
+
+/// A rule whose `End` token wasn't reached yet.
+struct OpenRule<'i> {
+    rule: String,
+    start: Position<'i>,
+    children: Vec<ParseTreeNode<'i>>,
+    /// Whether any `Start` token was found inside the rule.
+    had_inner: bool,
+}
+
+/// Applies the structural options of the `config` to the built nodes.
+fn finish<'i>(mut nodes: Vec<ParseTreeNode<'i>>, config: &TreeConfig) -> Vec<ParseTreeNode<'i>> {
+    if config.skip_last_top_level {
+        nodes.pop();
+    }
+    if config.collapse_unary_chains {
+        collapse_unary_chains(&mut nodes);
+    }
+    if config.merge_same_rule_leaves {
+        merge_same_rule_leaves(&mut nodes);
+    }
+
+    nodes
+}
+
+/// Formats the rule into its displayed name.
+///
+/// Grammars interpreted at runtime by `pest_vm` use `&str` as rule type,
+/// whose `Debug` output is quoted, so the quotes are removed again.
+pub(crate) fn rule_name<R>(rule: R) -> String
+where
+    R: pest::RuleType,
+{
+    let name = format!("{:?}", rule);
+
+    if name.len() >= 2 && name.starts_with('"') && name.ends_with('"') {
+        name[1..name.len() - 1].to_string()
+    } else {
+        name
+    }
+}
+
+fn build_nodes<'i, R>(
+    pairs: Pairs<'i, R>,
+    config: &TreeConfig,
+    decorate: &Decorate<'_, 'i, R>,
+    cmp: Option<&PairOrder<'_, 'i, R>>,
+) -> Vec<ParseTreeNode<'i>>
+where
+    R: pest::RuleType,
+{
+    let mut vec = Vec::new();
+
+    let mut pairs: Vec<_> = pairs.collect();
+    if let Some(cmp) = cmp {
+        pairs.sort_by(|a, b| cmp(a, b));
+    }
+    for pair in pairs {
+        let rule = rule_name(pair.as_rule());
+        if config.is_skipped(&rule) {
+            continue;
+        }
+
+        let inner = pair.clone().into_inner();
+        let had_inner = inner.peek().is_some();
+        let children = build_nodes(inner, config, decorate, cmp);
+
+        let mut node = ParseTreeNode {
+            rule,
+            span: pair.as_span(),
+            emptied: config.mark_emptied_nodes && had_inner && children.is_empty(),
+            children,
+            annotation: None,
+            merged: Vec::new(),
+            status: None,
+        };
+        decorate(&pair, &mut node);
+        if !is_hidden(&node, config) {
+            vec.push(node);
+        }
+    }
+
+    vec
+}
+
+/// Whether the node is left out for structural reasons, regardless of its
+/// name.
+fn is_hidden(node: &ParseTreeNode, config: &TreeConfig) -> bool {
+    config.hide_empty_leaves && node.is_leaf() && node.span.start() == node.span.end()
+}
+
+fn collapse_unary_chains(nodes: &mut [ParseTreeNode]) {
+    for node in nodes {
+        while node.children.len() == 1 {
+            let child = node.children.pop().unwrap();
+            node.rule = format!("{}>{}", node.rule, child.rule);
+            node.span = child.span;
+            node.children = child.children;
+            node.annotation = child.annotation.or(node.annotation.take());
+            node.status = child.status.or(node.status);
+        }
+        collapse_unary_chains(&mut node.children);
+    }
+}
+
+/// Merges every run of adjacent sibling leaves of the same rule into the
+/// first leaf of the run.
+fn merge_same_rule_leaves(nodes: &mut Vec<ParseTreeNode>) {
+    let mut merged: Vec<ParseTreeNode> = Vec::with_capacity(nodes.len());
+    for mut node in nodes.drain(..) {
+        merge_same_rule_leaves(&mut node.children);
+        match merged.last_mut() {
+            Some(last) if last.is_leaf() && node.is_leaf() && last.rule == node.rule => {
+                last.merged.push(node.content());
+            }
+            _ => merged.push(node),
+        }
+    }
+    *nodes = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ExpressionParser, Rule};
+    use pest::Parser;
+
+    #[test]
+    fn query() {
+        let tree =
+            ParseTree::new(ExpressionParser::parse(Rule::statement, "a + (b - c);").unwrap());
+
+        let contents = |nodes: Vec<&ParseTreeNode>| {
+            nodes
+                .into_iter()
+                .map(|node| node.content().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contents(tree.find_all("val")), ["a", "b", "c"]);
+        assert_eq!(contents(tree.find_all("op")), ["+", "-"]);
+        assert!(tree.find_all("block").is_empty());
+
+        let expr = tree.first("expr").unwrap();
+        assert_eq!(expr.content(), "a + (b - c)");
+        let rules: Vec<_> = expr.children().iter().map(|node| node.rule()).collect();
+        assert_eq!(rules, ["val", "op", "expr"]);
+        let rules: Vec<_> = expr.descendants().map(|node| node.rule()).collect();
+        assert_eq!(rules, ["val", "op", "expr", "val", "op", "val"]);
+        assert_eq!(tree.first("terminator").unwrap().span().start(), 11);
+
+        assert_eq!((&tree).into_iter().count(), 9);
+        assert_eq!(tree.roots().len(), 1);
+        assert!(tree.first("EOI").is_none());
+    }
+}
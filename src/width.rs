@@ -0,0 +1,154 @@
+//! Measuring and truncating text by its display width.
+//!
+//! With the `unicode-width` feature, wide characters (e.g. CJK) count as
+//! two columns and combining marks as zero. Without it, every `char`
+//! counts as one column.
+
+#[cfg(feature = "unicode-width")]
+fn char_width(c: char) -> usize {
+    unicode_width::UnicodeWidthChar::width(c).unwrap_or(0)
+}
+
+#[cfg(not(feature = "unicode-width"))]
+fn char_width(_c: char) -> usize {
+    1
+}
+
+/// The number of terminal columns needed to display `text`.
+pub(crate) fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Cuts `text` so that it, including a trailing `…`, fits into
+/// `max_width` columns. Text already fitting is returned unchanged.
+pub(crate) fn truncate(text: &str, max_width: usize) -> String {
+    truncate_marked(text, max_width, '…')
+}
+
+/// Like [`truncate`], but marks the cut by `marker` instead of `…`.
+pub(crate) fn truncate_marked(text: &str, max_width: usize, marker: char) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let mut width = 0;
+    let mut output = String::new();
+    for c in text.chars() {
+        width += char_width(c);
+        if width + 1 > max_width {
+            break;
+        }
+        output.push(c);
+    }
+    output.push(marker);
+
+    output
+}
+
+/// Like [`truncate`], but cuts `text` after the last `separator` fitting,
+/// so no token is cut in half. Without such a separator, `text` is cut
+/// like by [`truncate`].
+pub(crate) fn truncate_after(text: &str, max_width: usize, separator: char) -> String {
+    let truncated = truncate(text, max_width);
+    if truncated == text {
+        return truncated;
+    }
+
+    let kept = truncated.strip_suffix('…').unwrap_or(&truncated);
+    match kept.rfind(separator) {
+        Some(index) => format!("{}…", &kept[..index + separator.len_utf8()]),
+        None => truncated,
+    }
+}
+
+/// Replaces the middle of `text` by `…`, keeping `head` columns of its
+/// start and `tail` columns of its end. Text no wider than both ends is
+/// returned unchanged.
+pub(crate) fn truncate_middle(text: &str, head: usize, tail: usize) -> String {
+    if display_width(text) <= head + tail {
+        return text.to_string();
+    }
+
+    let take = |chars: &mut dyn Iterator<Item = char>, max_width| {
+        let mut width = 0;
+        let mut kept = Vec::new();
+        for c in chars {
+            width += char_width(c);
+            if width > max_width {
+                break;
+            }
+            kept.push(c);
+        }
+        kept
+    };
+    let start: String = take(&mut text.chars(), head).into_iter().collect();
+    let end: String = take(&mut text.chars().rev(), tail)
+        .into_iter()
+        .rev()
+        .collect();
+
+    format!("{}…{}", start, end)
+}
+
+/// Splits `text` into chunks of at most `max_width` columns. Every chunk
+/// holds at least one character, even if it's wider.
+pub(crate) fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let mut chunks = vec![String::new()];
+    let mut width = 0;
+    for c in text.chars() {
+        let c_width = char_width(c);
+        if width + c_width > max_width && width > 0 {
+            chunks.push(String::new());
+            width = 0;
+        }
+        width += c_width;
+        chunks.last_mut().unwrap().push(c);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_ascii() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abcdef", 1), "…");
+    }
+
+    #[test]
+    fn truncate_after_separator() {
+        assert_eq!(truncate_after("1,22,333", 8, ','), "1,22,333");
+        assert_eq!(truncate_after("1,22,333", 7, ','), "1,22,…");
+        assert_eq!(truncate_after("1,22,333", 4, ','), "1,…");
+        assert_eq!(truncate_after("12345", 4, ','), "123…");
+        assert_eq!(truncate_after("1…2…345", 5, '…'), "1…2……");
+    }
+
+    #[test]
+    fn truncate_middle_ascii() {
+        assert_eq!(truncate_middle("\"abcdef\"", 3, 2), "\"ab…f\"");
+        assert_eq!(truncate_middle("abcde", 3, 2), "abcde");
+        assert_eq!(truncate_middle("abcdef", 0, 0), "…");
+    }
+
+    #[test]
+    fn wrap_ascii() {
+        assert_eq!(wrap("abcdefg", 3), ["abc", "def", "g"]);
+        assert_eq!(wrap("abc", 3), ["abc"]);
+        assert_eq!(wrap("", 3), [""]);
+        assert_eq!(wrap("ab", 0), ["a", "b"]);
+    }
+
+    #[cfg(feature = "unicode-width")]
+    #[test]
+    fn truncate_full_width() {
+        assert_eq!(display_width("漢字"), 4);
+        assert_eq!(truncate("漢字漢字", 6), "漢字…");
+        assert_eq!(truncate("漢字漢字", 5), "漢字…");
+        assert_eq!(truncate("漢字漢字", 4), "漢…");
+    }
+}